@@ -1,24 +1,72 @@
+use std::collections::HashSet;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail};
+use byte_unit::Byte;
+use crossterm::event::{KeyEvent, MouseButton, MouseEvent, MouseEventKind};
 use derivative::Derivative;
 use log::LevelFilter;
+use ratatui::layout::Rect;
+use ratatui::widgets::ListState;
 
 use diskscan::{
-    EntryPath, EntrySnapshot, EntrySnapshotRef, ScanStats, Scanner, ScannerBuilder, SnapshotConfig,
-    TreeSnapshot,
+    DeletedFile, EntryPath, EntrySnapshot, EntrySnapshotRef, ScanError, ScanStats, Scanner,
+    ScannerBuilder, SnapshotConfig, SortOrder, TreeSnapshot,
 };
 
-use crate::dialog::{DeleteDialog, Dialog, NewScanDialog, ScanStatsDialog};
-use crate::file_list::FileListState;
+use crate::dialog::{
+    DeleteDialog, Dialog, GoToDialog, NewScanDialog, ScanStatsDialog, UsedSpaceDialog,
+};
+use crate::external::{ExternalCommand, Program};
+use crate::file_list::{Column, FileListState};
+use crate::keymap::{Action, Keymap};
 use crate::log_list::LogListState;
 use crate::logger::{LogEntry, Logger};
-use crate::term::{InputHandler, InputProvider};
+use crate::recent::RecentRoots;
+use crate::term::{self, InputHandler, InputProvider};
+use crate::theme::Theme;
+use crate::treemap::{Move, TreemapState};
+use crate::utils;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Screen {
     Help,
     Files,
+    Errors,
+    Deleted,
     Log,
 }
 
+/// How entries of opened directory are shown on files screen
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FilesView {
+    List,
+    Tree,
+    Treemap,
+}
+
+/// Entry shown as a row when files are shown as tree
+#[derive(Debug)]
+pub struct TreeRow<'a> {
+    pub entry: EntrySnapshotRef<'a, EntrySnapshot>,
+    pub path: EntryPath,
+    /// For entry and each of its parents below opened directory,
+    /// whether it is the last child, used to draw indentation guides
+    pub is_last: Vec<bool>,
+    pub is_expanded: bool,
+}
+
+/// Number of rows skipped by 'PageUp' and 'PageDown' in lists of errors and deleted files
+const LIST_PAGE: usize = 10;
+
+/// Number of previously opened directories that are remembered for back navigation
+const MAX_HISTORY: usize = 100;
+
+/// Maximum time between two clicks at the same cell to count them as double click
+const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(500);
+
 #[derive(Debug)]
 pub struct LogsApp {
     pub logger: &'static Logger,
@@ -66,40 +114,349 @@ impl InputHandler for LogsApp {
     }
 }
 
+/// Selectable list of entries shown on its own screen
+#[derive(Debug)]
+pub struct ListApp<T> {
+    pub entries: Vec<T>,
+    pub list_state: ListState,
+    /// Area of entries during last render
+    area: Rect,
+    /// Index of the first shown entry, tracked the same way as in `List` widget
+    offset: usize,
+}
+
+impl<T> Default for ListApp<T> {
+    fn default() -> Self {
+        Self {
+            entries: vec![],
+            list_state: ListState::default(),
+            area: Rect::default(),
+            offset: 0,
+        }
+    }
+}
+
+impl<T> ListApp<T> {
+    pub fn get_selected(&self) -> Option<&T> {
+        self.list_state.selected().and_then(|i| self.entries.get(i))
+    }
+
+    /// Returns index of entry that is drawn at given cell
+    pub fn item_at(&self, x: u16, y: u16) -> Option<usize> {
+        if !utils::contains(self.area, x, y) {
+            return None;
+        }
+        let index = self.offset + (y - self.area.y) as usize;
+        (index < self.entries.len()).then_some(index)
+    }
+
+    fn select(&mut self, index: usize) {
+        if self.entries.is_empty() {
+            self.list_state.select(None);
+        } else {
+            self.list_state
+                .select(Some(index.min(self.entries.len() - 1)));
+        }
+    }
+
+    /// Selects entry that is drawn at given cell, returns `false` if there is none
+    fn select_at(&mut self, x: u16, y: u16) -> bool {
+        match self.item_at(x, y) {
+            Some(index) => {
+                self.select(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remembers area that entries are rendered to
+    ///
+    /// Offset is scrolled so selected entry is visible, the same way as widget does it
+    /// for single line entries
+    pub fn set_area(&mut self, area: Rect) {
+        self.area = area;
+        let height = (area.height as usize).max(1);
+        let selected = self.list_state.selected().unwrap_or(0);
+        self.offset = self.offset.min(self.entries.len().saturating_sub(1));
+        if selected >= self.offset + height {
+            self.offset = selected + 1 - height;
+        } else if selected < self.offset {
+            self.offset = selected;
+        }
+    }
+
+    fn set_entries(&mut self, entries: Vec<T>) {
+        self.entries = entries;
+        self.select(self.list_state.selected().unwrap_or(0));
+    }
+}
+
+impl<T> InputHandler for ListApp<T> {
+    fn on_down(&mut self) {
+        self.select(self.list_state.selected().map_or(0, |i| i + 1));
+    }
+
+    fn on_end(&mut self) {
+        self.select(usize::MAX);
+    }
+
+    fn on_home(&mut self) {
+        self.select(0);
+    }
+
+    fn on_page_down(&mut self) {
+        self.select(self.list_state.selected().unwrap_or(0) + LIST_PAGE);
+    }
+
+    fn on_page_up(&mut self) {
+        self.select(
+            self.list_state
+                .selected()
+                .unwrap_or(0)
+                .saturating_sub(LIST_PAGE),
+        );
+    }
+
+    fn on_up(&mut self) {
+        self.select(self.list_state.selected().unwrap_or(0).saturating_sub(1));
+    }
+}
+
 #[derive(Debug)]
 pub struct FilesApp {
     pub scanner: Scanner,
     pub file_list_state: FileListState,
+    pub treemap_state: TreemapState,
+    pub view: FilesView,
+    pub sort: SortOrder,
+    /// Directories that are expanded in tree view
+    pub expanded: HashSet<EntryPath>,
     pub current_path: EntryPath,
     pub path_history: Vec<String>,
+    /// Previously opened directories, most recent last
+    pub back: Vec<EntryPath>,
+    /// Directories that were left with back, most recent last
+    pub forward: Vec<EntryPath>,
+    /// Areas of path segments in title during last render and directories that they open
+    pub breadcrumb_areas: Vec<(Rect, EntryPath)>,
     pub snapshot: Option<TreeSnapshot<EntrySnapshot>>,
     pub stats: ScanStats,
+    pub errors: ListApp<(EntryPath, ScanError)>,
+    /// Deleted files on scanned filesystem that are still open
+    pub deleted: ListApp<DeletedFile>,
+    /// Receives deleted files while they are searched in background
+    deleted_receiver: Option<Receiver<Vec<DeletedFile>>>,
+    /// Whether scanner was busy during last update, used to detect end of scan
+    was_scanning: bool,
 }
 
 impl FilesApp {
-    pub fn new_scan(path: String) -> Self {
-        let scanner = ScannerBuilder::default().scan(path);
+    pub fn new_scan(builder: ScannerBuilder, path: String, sort: SortOrder) -> Self {
+        let scanner = builder.scan(path);
         let file_list_state = FileListState::default();
         let current_path = scanner.get_scan_path().clone();
         let stats = scanner.stats();
         FilesApp {
             scanner,
             file_list_state,
+            treemap_state: TreemapState::default(),
+            view: FilesView::List,
+            sort,
+            expanded: HashSet::new(),
             current_path,
             path_history: vec![],
+            back: vec![],
+            forward: vec![],
+            breadcrumb_areas: vec![],
             snapshot: None,
             stats,
+            errors: ListApp::default(),
+            deleted: ListApp::default(),
+            deleted_receiver: None,
+            was_scanning: true,
         }
     }
 
-    pub fn get_selected(&self) -> Option<EntrySnapshotRef<EntrySnapshot>> {
-        self.snapshot
-            .as_ref()
-            .and_then(|s| s.get_root().get_nth_child(self.file_list_state.selected()))
+    /// Returns command that starts program at selected entry
+    ///
+    /// Shell is started in selected directory or in opened one if file is selected,
+    /// pager and editor accept only files
+    pub fn get_external_command(&self, program: Program) -> Option<ExternalCommand> {
+        let is_dir = self.get_selected().is_some_and(|entry| entry.is_dir());
+        let (path, rescan) = match (program, self.get_selected_path()) {
+            (Program::Shell, _) | (Program::Opener, None) => {
+                let dir = self
+                    .get_selected_dir()
+                    .unwrap_or_else(|| self.current_path.clone());
+                (dir.clone(), dir)
+            }
+            (Program::Opener, Some(path)) if is_dir => (path.clone(), path),
+            (_, Some(_)) if is_dir => {
+                log::warn!("Only files can be opened in pager or editor");
+                return None;
+            }
+            (_, Some(path)) => {
+                let mut parent = path.clone();
+                parent.go_up();
+                (path, parent)
+            }
+            (_, None) => return None,
+        };
+        Some(ExternalCommand {
+            program,
+            path: path.get_path(),
+            rescan,
+        })
+    }
+
+    /// Returns total size of deleted files that are still open
+    pub fn get_deleted_size(&self) -> Byte {
+        Byte::from_bytes(
+            self.deleted
+                .entries
+                .iter()
+                .map(|f| f.size.get_bytes())
+                .sum(),
+        )
+    }
+
+    pub fn get_selected(&self) -> Option<EntrySnapshotRef<'_, EntrySnapshot>> {
+        let root = self.snapshot.as_ref()?.get_root();
+        let selected = self.file_list_state.selected();
+        if self.view == FilesView::Tree {
+            self.get_tree_rows(root)
+                .into_iter()
+                .nth(selected)
+                .map(|row| row.entry)
+        } else {
+            root.get_nth_child(selected)
+        }
+    }
+
+    /// Returns full path of selected entry if it is a directory
+    pub fn get_selected_dir(&self) -> Option<EntryPath> {
+        self.get_selected().filter(|e| e.is_dir())?;
+        self.get_selected_path()
+    }
+
+    /// Returns full path of selected entry
+    pub fn get_selected_path(&self) -> Option<EntryPath> {
+        let root = self.snapshot.as_ref()?.get_root();
+        let selected = self.file_list_state.selected();
+        if self.view == FilesView::Tree {
+            self.get_tree_rows(root)
+                .into_iter()
+                .nth(selected)
+                .map(|row| row.path)
+        } else {
+            let entry = root.get_nth_child(selected)?;
+            let mut path = self.current_path.clone();
+            path.join(entry.get_name().to_string());
+            Some(path)
+        }
+    }
+
+    /// Returns rows of tree view for given snapshot of opened directory
+    pub fn get_tree_rows<'a>(&self, root: EntrySnapshotRef<'a, EntrySnapshot>) -> Vec<TreeRow<'a>> {
+        let mut rows = vec![];
+        self.add_tree_rows(&mut rows, root, &self.current_path, &mut vec![]);
+        rows
+    }
+
+    /// Returns config of snapshot that is required to show opened directory in current view
+    pub fn get_snapshot_config(&self) -> SnapshotConfig {
+        SnapshotConfig {
+            max_depth: if self.view == FilesView::Tree {
+                self.get_tree_depth()
+            } else {
+                1
+            },
+            min_size: 0,
+            // treemap is built from the largest entries
+            sort: if self.view == FilesView::Treemap {
+                SortOrder::Size
+            } else {
+                self.sort
+            },
+        }
+    }
+
+    /// Returns depth of snapshot that is required to show all expanded directories
+    fn get_tree_depth(&self) -> usize {
+        let depth = self.current_path.parts().len();
+        self.expanded
+            .iter()
+            .filter(|path| **path > self.current_path)
+            .map(|path| path.parts().len() - depth + 1)
+            .max()
+            .unwrap_or(1)
+    }
+
+    /// Returns index of entry that is drawn at given cell
+    pub fn item_at(&self, x: u16, y: u16) -> Option<usize> {
+        match self.view {
+            FilesView::List | FilesView::Tree => self.file_list_state.item_at(x, y),
+            FilesView::Treemap => self.treemap_state.item_at(x, y),
+        }
+    }
+
+    /// Opens directory that was opened before current one
+    pub fn go_back(&mut self) {
+        if let Some(path) = self.back.pop() {
+            self.forward.push(self.current_path.clone());
+            self.show_path(path);
+        }
+    }
+
+    /// Opens directory that was left with back
+    pub fn go_forward(&mut self) {
+        if let Some(path) = self.forward.pop() {
+            self.back.push(self.current_path.clone());
+            self.show_path(path);
+        }
+    }
+
+    /// Opens directory with given path or its parent if path is a file
+    ///
+    /// Relative paths are resolved from working directory like in new scan dialog,
+    /// path must be inside of scanned directory
+    pub fn go_to(&mut self, text: &str) -> anyhow::Result<()> {
+        let root = self.scanner.get_scan_path();
+        // joining with absolute path replaces working directory
+        let current_dir = std::env::current_dir()?;
+        let full = utils::normalize_path(&current_dir.join(text));
+        let root_path = utils::normalize_path(&current_dir.join(root.get_path()));
+        let relative = full
+            .strip_prefix(root_path)
+            .map_err(|_| anyhow!("'{}' is outside of scanned directory", full.display()))?;
+        let mut path = root.clone();
+        for part in relative {
+            let part = part
+                .to_str()
+                .ok_or_else(|| anyhow!("'{}' is not valid unicode", full.display()))?;
+            path.join(part.to_string());
+        }
+        if full.symlink_metadata().is_err() {
+            bail!("'{}' does not exist", full.display());
+        }
+
+        if self.scanner.has_dir(&path) {
+            self.open_path(path);
+        } else {
+            let mut parent = path.clone();
+            parent.go_up();
+            if path.is_root() || !self.scanner.has_dir(&parent) {
+                bail!("'{}' is not scanned", full.display());
+            }
+            self.reveal(&path);
+        }
+        Ok(())
     }
 
     pub fn go_up(&mut self) {
         if !self.current_path.is_root() {
+            self.push_history();
             if let Some(entry) = self.get_selected() {
                 // save selected entry name so if we open again this directory, it is selected again
                 self.path_history.push(entry.get_name().to_string());
@@ -111,29 +468,137 @@ impl FilesApp {
         }
     }
 
-    pub fn open_selected(&mut self) {
-        if let Some(entry) = self.get_selected() {
-            if entry.is_dir() {
-                self.current_path.join(entry.get_name().to_string());
-                self.file_list_state.select(0);
-                self.snapshot = None;
+    /// Returns whether virtual entry with deleted files is shown in opened directory
+    pub fn has_deleted_entry(&self) -> bool {
+        self.current_path.is_root() && !self.deleted.entries.is_empty()
+    }
+
+    /// Returns whether virtual entry with deleted files is selected
+    pub fn is_deleted_selected(&self) -> bool {
+        // virtual entry is always the last one
+        self.has_deleted_entry()
+            && self
+                .snapshot
+                .as_ref()
+                .map(|s| match self.view {
+                    FilesView::Tree => self.get_tree_rows(s.get_root()).len(),
+                    _ => s.get_root().get_children_count(),
+                })
+                .map(|count| count == self.file_list_state.selected())
+                .unwrap_or(false)
+    }
+
+    /// Returns whether deleted files are being searched
+    pub fn is_searching_deleted(&self) -> bool {
+        self.deleted_receiver.is_some()
+    }
+
+    /// Collapses selected directory in tree view
+    ///
+    /// If it is already collapsed, its parent is selected or opened directory is left
+    pub fn collapse_selected(&mut self) {
+        if let Some(path) = self.get_selected_path() {
+            if self.expanded.remove(&path) {
                 self.update_snapshot();
-                if self
-                    .snapshot
-                    .as_ref()
-                    .map(|s| s.get_root().get_children_count())
-                    .unwrap_or(0)
-                    == 0
-                {
-                    // dir doesn't have children, try to rescan it
-                    self.rescan(false);
+            } else if path.parts().len() > self.current_path.parts().len() + 1 {
+                let mut parent = path;
+                parent.go_up();
+                self.select_path(&parent);
+            } else {
+                self.go_up();
+            }
+        }
+    }
+
+    /// Expands selected directory in tree view, if it is already expanded,
+    /// its first child is selected
+    pub fn expand_selected(&mut self) {
+        if let Some(path) = self.get_selected_dir() {
+            if self.expanded.insert(path) {
+                self.update_snapshot();
+            } else {
+                self.select_down();
+            }
+        }
+    }
+
+    /// Selects entry next to selected one in treemap
+    pub fn move_selection(&mut self, direction: Move) {
+        if let Some(index) = self
+            .treemap_state
+            .neighbor(self.file_list_state.selected(), direction)
+        {
+            self.file_list_state.select(index);
+        }
+    }
+
+    /// Opens directory with given path and remembers current one in history
+    pub fn open_path(&mut self, path: EntryPath) {
+        if path != self.current_path {
+            self.push_history();
+            self.show_path(path);
+        }
+    }
+
+    pub fn open_selected(&mut self) {
+        if let Some(path) = self.get_selected_dir() {
+            self.push_history();
+            self.current_path = path;
+            self.file_list_state.select(0);
+            self.snapshot = None;
+            self.update_snapshot();
+            if self
+                .snapshot
+                .as_ref()
+                .map(|s| s.get_root().get_children_count())
+                .unwrap_or(0)
+                == 0
+            {
+                // dir doesn't have children, try to rescan it
+                self.rescan(false);
+            }
+            if let Some(name) = self.path_history.pop() {
+                if !self.select_entry(&name) {
+                    // we opened some other dir, so clear history
+                    self.path_history.clear();
                 }
-                if let Some(name) = self.path_history.pop() {
-                    if !self.select_entry(&name) {
-                        // we opened some other dir, so clear history
-                        self.path_history.clear();
-                    }
+            }
+        }
+    }
+
+    /// Opens parent of specified entry and selects this entry
+    ///
+    /// Root has no parent, so it is opened itself
+    pub fn reveal(&mut self, path: &EntryPath) {
+        if path.is_root() {
+            self.open_path(path.clone());
+            return;
+        }
+        let mut parent = path.clone();
+        parent.go_up();
+        self.open_path(parent);
+        self.select_entry(path.get_name());
+    }
+
+    /// Remembers opened directory so it can be opened again with back
+    fn push_history(&mut self) {
+        self.back.push(self.current_path.clone());
+        if self.back.len() > MAX_HISTORY {
+            self.back.remove(0);
+        }
+        self.forward.clear();
+    }
+
+    /// Applies deleted files if their search is finished
+    fn receive_deleted(&mut self) {
+        if let Some(receiver) = self.deleted_receiver.as_ref() {
+            match receiver.try_recv() {
+                Ok(files) => {
+                    self.deleted.set_entries(files);
+                    self.deleted_receiver = None;
                 }
+                Err(TryRecvError::Empty) => {}
+                Err(TryRecvError::Disconnected) => self.deleted_receiver = None,
             }
         }
     }
@@ -148,12 +613,29 @@ impl FilesApp {
             .select(self.file_list_state.selected() + 1);
     }
 
+    /// Selects child of opened directory with given name
     pub fn select_entry(&mut self, name: &str) -> bool {
+        let mut path = self.current_path.clone();
+        path.join(name.to_string());
+        self.select_path(&path)
+    }
+
+    /// Selects entry with given full path if it is visible
+    pub fn select_path(&mut self, path: &EntryPath) -> bool {
         if let Some(pos) = self.snapshot.as_ref().and_then(|snapshot| {
-            snapshot
-                .get_root()
-                .iter()
-                .position(|e| e.get_name() == name)
+            let root = snapshot.get_root();
+            if self.view == FilesView::Tree {
+                self.get_tree_rows(root)
+                    .iter()
+                    .position(|row| &row.path == path)
+            } else {
+                let mut parent = path.clone();
+                parent.go_up();
+                if parent != self.current_path || path.is_root() {
+                    return None;
+                }
+                root.iter().position(|e| e.get_name() == path.get_name())
+            }
         }) {
             self.file_list_state.select(pos);
             true
@@ -167,11 +649,29 @@ impl FilesApp {
             .select(self.file_list_state.selected().saturating_sub(1));
     }
 
+    /// Opens directory without changing history
+    ///
+    /// If opened directory is a parent of previous one,
+    /// child that contains previous directory is selected
+    fn show_path(&mut self, path: EntryPath) {
+        let previous = std::mem::replace(&mut self.current_path, path);
+        self.path_history.clear();
+        self.file_list_state.select(0);
+        self.snapshot = None;
+        self.update_snapshot();
+        if previous > self.current_path {
+            let name = previous.parts()[self.current_path.parts().len()].clone();
+            self.select_entry(&name);
+        }
+    }
+
     pub fn tab_title(&self) -> String {
-        let suffix = if self.scanner.is_scanning() {
-            " (scanning)"
-        } else {
-            ""
+        let suffix = match (self.scanner.is_scanning(), self.scanner.is_paused()) {
+            (true, true) => " (scanning, paused)",
+            (true, false) => " (scanning)",
+            (false, true) => " (paused)",
+            (false, false) if self.stats.incomplete_dirs > 0 => " (incomplete)",
+            (false, false) => "",
         };
         format!(
             "Files at {}{}",
@@ -180,22 +680,83 @@ impl FilesApp {
         )
     }
 
+    /// Expands or collapses selected directory in tree view
+    pub fn toggle_expanded(&mut self) {
+        if let Some(path) = self.get_selected_dir() {
+            if !self.expanded.remove(&path) {
+                self.expanded.insert(path);
+            }
+            self.update_snapshot();
+        }
+    }
+
+    /// Switches between list and given view
+    pub fn toggle_view(&mut self, view: FilesView) {
+        let selected = self.get_selected_path();
+        self.view = if self.view == view {
+            FilesView::List
+        } else {
+            view
+        };
+        self.update_snapshot();
+        if let Some(path) = selected {
+            // selection in tree view is kept only if entry is still visible
+            if !self.select_path(&path) {
+                self.file_list_state.select(0);
+            }
+        }
+    }
+
+    pub fn toggle_pause(&mut self) {
+        if self.scanner.is_paused() {
+            self.scanner.resume();
+        } else {
+            self.scanner.pause();
+        }
+    }
+
+    /// Starts search of deleted files on worker thread
+    ///
+    /// It requires checking open files of all processes, so it can take a while.
+    /// Found files are applied in `update_snapshot`
+    pub fn update_deleted(&mut self) {
+        if self.deleted_receiver.is_some() {
+            return;
+        }
+        let (sender, receiver) = mpsc::channel();
+        let path = self.scanner.get_scan_path().get_path();
+        let spawned = thread::Builder::new()
+            .name("deleted-files".to_string())
+            .spawn(move || {
+                let _ = sender.send(diskscan::get_deleted_files(path));
+            });
+        match spawned {
+            Ok(_) => self.deleted_receiver = Some(receiver),
+            Err(e) => log::error!("Failed to search deleted files: {}", e),
+        }
+    }
+
+    pub fn update_errors(&mut self) {
+        self.errors.set_entries(self.scanner.get_errors());
+    }
+
     pub fn update_snapshot(&mut self) {
-        let selected = self.snapshot.as_ref().and_then(|snapshot| {
-            snapshot
-                .get_root()
-                .get_nth_child(self.file_list_state.selected())
-                .map(|e| e.get_name().to_string())
-        });
+        let selected = self.get_selected_path();
+
+        // deleted files are searched only after scan since it requires checking all processes
+        let is_scanning = self.scanner.is_scanning();
+        if self.was_scanning && !is_scanning {
+            self.update_deleted();
+        }
+        self.was_scanning = is_scanning;
+        self.receive_deleted();
 
+        // finish scan of opened directory first
+        self.scanner.set_focus(Some(self.current_path.clone()));
         self.stats = self.scanner.stats();
-        self.snapshot = self.scanner.get_tree(
-            &self.current_path,
-            SnapshotConfig {
-                max_depth: 1,
-                min_size: 0,
-            },
-        );
+        self.snapshot = self
+            .scanner
+            .get_tree(&self.current_path, self.get_snapshot_config());
         let scanned_path = self.scanner.get_current_scan_path();
         self.file_list_state.set_busy_item(None);
         if let Some(snapshot) = self.snapshot.as_ref() {
@@ -204,7 +765,14 @@ impl FilesApp {
                 self.stats.used_size = snapshot.get_root().get_size()
             }
             if let Some(path) = scanned_path {
-                if path > self.current_path {
+                if self.view == FilesView::Tree {
+                    // the deepest visible parent of scanned directory is busy
+                    self.file_list_state.set_busy_item(
+                        self.get_tree_rows(snapshot.get_root())
+                            .iter()
+                            .rposition(|row| row.path <= path),
+                    );
+                } else if path > self.current_path {
                     let name = &path.parts()[self.current_path.parts().len()];
                     self.file_list_state.set_busy_item(
                         snapshot
@@ -216,8 +784,34 @@ impl FilesApp {
             }
         }
 
-        if let Some(name) = selected {
-            self.select_entry(&name);
+        if let Some(path) = selected {
+            self.select_path(&path);
+        }
+    }
+
+    fn add_tree_rows<'a>(
+        &self,
+        rows: &mut Vec<TreeRow<'a>>,
+        entry: EntrySnapshotRef<'a, EntrySnapshot>,
+        path: &EntryPath,
+        is_last: &mut Vec<bool>,
+    ) {
+        let count = entry.get_children_count();
+        for (i, child) in entry.iter().enumerate() {
+            let mut child_path = path.clone();
+            child_path.join(child.get_name().to_string());
+            let is_expanded = child.is_dir() && self.expanded.contains(&child_path);
+            is_last.push(i + 1 == count);
+            rows.push(TreeRow {
+                entry: child.clone(),
+                path: child_path.clone(),
+                is_last: is_last.clone(),
+                is_expanded,
+            });
+            if is_expanded {
+                self.add_tree_rows(rows, child, &child_path, is_last);
+            }
+            is_last.pop();
         }
     }
 }
@@ -229,13 +823,29 @@ pub struct App {
     pub screen: Screen,
     #[derivative(Debug = "ignore")]
     pub dialog: Option<Box<dyn Dialog>>,
-    pub dialog_menu: Option<usize>,
+    /// Tab that is highlighted while dialog is shown
+    pub dialog_tab: Option<Action>,
     pub should_quit: bool,
     pub logs_app: LogsApp,
+    pub scanner_builder: ScannerBuilder,
+    /// Paths of previous scans
+    pub recent_roots: RecentRoots,
+    pub keymap: Keymap,
+    /// Order of files in new scans
+    pub sort: SortOrder,
+    /// Columns of files list
+    pub columns: Vec<Column>,
+    pub theme: Theme,
+    /// Program that should be started while terminal is suspended
+    pub external: Option<ExternalCommand>,
+    /// Areas of tabs during last render and actions that they trigger
+    pub tab_areas: Vec<(Rect, Action)>,
+    /// Time and cell of last click, used to detect double click
+    last_click: Option<(Instant, u16, u16)>,
 }
 
 impl App {
-    pub fn new() -> Self {
+    pub fn new(scanner_builder: ScannerBuilder, keymap: Keymap) -> Self {
         // register our logger
         log::set_logger(Logger::global()).unwrap();
         log::set_max_level(LevelFilter::Info);
@@ -244,9 +854,18 @@ impl App {
             files: None,
             screen: Screen::Help,
             dialog: None,
-            dialog_menu: None,
+            dialog_tab: None,
             should_quit: false,
             logs_app: LogsApp::new(),
+            scanner_builder,
+            recent_roots: RecentRoots::default(),
+            keymap,
+            sort: SortOrder::default(),
+            columns: Column::DEFAULT.to_vec(),
+            theme: Theme::default(),
+            external: None,
+            tab_areas: vec![],
+            last_click: None,
         }
     }
 
@@ -256,51 +875,276 @@ impl App {
             if let Err(dialog) = dialog.try_finish(self) {
                 self.dialog = Some(dialog);
             } else {
-                self.dialog_menu = None;
+                self.dialog_tab = None;
             }
         } else {
             let _ = provider.provide(self);
         }
     }
 
+    /// Rescans directory that could be changed by external program
+    pub fn on_external_exit(&mut self, command: ExternalCommand, result: anyhow::Result<()>) {
+        if let Err(e) = result {
+            log::error!("{:#}", e);
+        }
+        if let Some(files) = self.files.as_ref() {
+            files.scanner.rescan_path(command.rescan, false);
+        }
+    }
+
     pub fn on_tick(&mut self) {
         self.logs_app.on_tick();
         self.files.as_mut().map(FilesApp::update_snapshot);
+        if self.screen == Screen::Errors {
+            self.files.as_mut().map(FilesApp::update_errors);
+        }
     }
 
-    pub fn selected_tab(&self) -> usize {
-        let add = if self.files.is_none() { 0 } else { 1 };
-
-        if let Some(dialog) = self.dialog_menu {
-            dialog + add
+    /// Selects clicked entry or tab, double click opens entry
+    ///
+    /// Clicked part of path in title opens that directory
+    fn on_click(&mut self, x: u16, y: u16) {
+        let is_double = self
+            .last_click
+            .map(|(time, last_x, last_y)| {
+                time.elapsed() < DOUBLE_CLICK_TIME && last_x == x && last_y == y
+            })
+            .unwrap_or(false);
+        // third click starts new double click
+        self.last_click = if is_double {
+            None
         } else {
-            match self.screen {
-                Screen::Files => 0,
-                Screen::Help => add,
-                Screen::Log => add + 2,
+            Some((Instant::now(), x, y))
+        };
+
+        if let Some(&(_, action)) = self
+            .tab_areas
+            .iter()
+            .find(|(area, _)| utils::contains(*area, x, y))
+        {
+            self.on_action(action);
+        } else if self.screen == Screen::Files {
+            let files = self.files.as_mut().unwrap();
+            if let Some((_, path)) = files
+                .breadcrumb_areas
+                .iter()
+                .find(|(area, _)| utils::contains(*area, x, y))
+            {
+                files.open_path(path.clone());
+            } else if let Some(index) = files.item_at(x, y) {
+                files.file_list_state.select(index);
+                if is_double {
+                    self.on_enter();
+                }
+            }
+        } else if self.screen == Screen::Log {
+            let state = &mut self.logs_app.list_state;
+            if let Some(index) = state.item_at(x, y) {
+                state.select(Some(index));
+            }
+        } else if let Some(files) = self.files.as_mut() {
+            let clicked = match self.screen {
+                Screen::Errors => files.errors.select_at(x, y),
+                Screen::Deleted => files.deleted.select_at(x, y),
+                _ => false,
+            };
+            if clicked && is_double {
+                self.on_enter();
+            }
+        }
+    }
+
+    /// Performs action bound to pressed key
+    fn on_action(&mut self, action: Action) {
+        match action {
+            Action::Back => self.on_backspace(),
+            Action::Down => self.on_down(),
+            Action::End => self.on_end(),
+            Action::Home => self.on_home(),
+            Action::Left => self.on_left(),
+            Action::Open => self.on_enter(),
+            Action::PageDown => self.on_page_down(),
+            Action::PageUp => self.on_page_up(),
+            Action::Right => self.on_right(),
+            Action::Up => self.on_up(),
+            Action::Expand if self.is_tree() => self.files.as_mut().unwrap().toggle_expanded(),
+            Action::Cancel if self.files.is_some() => self.files.as_ref().unwrap().scanner.cancel(),
+            Action::Copy if self.screen == Screen::Files => {
+                let files = self.files.as_ref().unwrap();
+                if let Some(path) = files.get_selected_path() {
+                    let path = path.get_path().display().to_string();
+                    match term::copy_to_clipboard(&path) {
+                        Ok(()) => log::info!("Copied '{}' to clipboard", path),
+                        Err(e) => log::error!("Failed to copy path: {:#}", e),
+                    }
+                }
             }
+            Action::Copy if self.screen == Screen::Log => {
+                let logs = &self.logs_app;
+                if let Some(entry) = logs.list_state.selected().and_then(|i| logs.entries.get(i)) {
+                    let text = entry.text.clone();
+                    match term::copy_to_clipboard(&text) {
+                        Ok(()) => log::info!("Copied log entry to clipboard"),
+                        Err(e) => log::error!("Failed to copy log entry: {:#}", e),
+                    }
+                }
+            }
+            Action::Delete if self.screen == Screen::Files => {
+                let files = self.files.as_ref().unwrap();
+                if let (Some(entry), Some(path)) = (files.get_selected(), files.get_selected_path())
+                {
+                    self.dialog = Some(Box::new(DeleteDialog::new(path, entry.get_size())));
+                    self.dialog_tab = Some(Action::Delete);
+                }
+            }
+            Action::Errors if self.files.is_some() => {
+                self.files.as_mut().map(FilesApp::update_errors);
+                self.screen = Screen::Errors;
+            }
+            Action::Editor | Action::OpenExternal | Action::Pager | Action::Shell
+                if self.screen == Screen::Files =>
+            {
+                let program = match action {
+                    Action::Editor => Program::Editor,
+                    Action::OpenExternal => Program::Opener,
+                    Action::Pager => Program::Pager,
+                    _ => Program::Shell,
+                };
+                self.external = self.files.as_ref().unwrap().get_external_command(program);
+            }
+            Action::Files if self.files.is_some() => self.screen = Screen::Files,
+            Action::GoTo if self.screen == Screen::Files => {
+                let files = self.files.as_ref().unwrap();
+                let history = files.back.iter().map(|path| path.to_string()).collect();
+                self.dialog = Some(Box::new(GoToDialog::new(
+                    files.current_path.to_string(),
+                    history,
+                )));
+            }
+            Action::HistoryBack if self.screen == Screen::Files => {
+                self.files.as_mut().unwrap().go_back()
+            }
+            Action::HistoryForward if self.screen == Screen::Files => {
+                self.files.as_mut().unwrap().go_forward()
+            }
+            Action::UsedSpace if self.screen == Screen::Files => {
+                self.dialog = Some(Box::new(UsedSpaceDialog::new()));
+                self.dialog_tab = Some(Action::Stats);
+            }
+            Action::Help => self.screen = Screen::Help,
+            Action::Log => {
+                // follow only if log screen was not opened yet
+                self.logs_app
+                    .list_state
+                    .set_follow(self.screen != Screen::Log);
+                self.screen = Screen::Log;
+            }
+            Action::NewScan => {
+                self.dialog = Some(Box::new(NewScanDialog::new(
+                    diskscan::get_mounts(self.scanner_builder.get_fs_overrides()),
+                    self.recent_roots.paths().to_vec(),
+                )));
+                self.dialog_tab = Some(Action::NewScan);
+            }
+            Action::ScanSelected if self.screen == Screen::Files => {
+                if let Some(path) = self.files.as_ref().unwrap().get_selected_dir() {
+                    self.start_scan(path.to_string());
+                }
+            }
+            Action::Pause if self.files.is_some() => self.files.as_mut().unwrap().toggle_pause(),
+            Action::Rescan if self.screen == Screen::Files => {
+                self.files.as_mut().unwrap().rescan(true)
+            }
+            Action::Rescan if self.screen == Screen::Deleted => {
+                self.files.as_mut().unwrap().update_deleted()
+            }
+            Action::Quit => self.should_quit = true,
+            Action::Stats if self.screen == Screen::Files => {
+                self.dialog = Some(Box::new(ScanStatsDialog::new()));
+                self.dialog_tab = Some(Action::Stats);
+            }
+            Action::Treemap if self.screen == Screen::Files => {
+                self.files.as_mut().unwrap().toggle_view(FilesView::Treemap)
+            }
+            Action::Deleted if self.files.is_some() => {
+                self.files.as_mut().map(FilesApp::update_deleted);
+                self.screen = Screen::Deleted;
+            }
+            Action::Tree if self.screen == Screen::Files => {
+                self.files.as_mut().unwrap().toggle_view(FilesView::Tree)
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns list that is shown on current screen
+    fn get_list(&mut self) -> Option<&mut dyn InputHandler> {
+        match self.screen {
+            Screen::Errors => Some(&mut self.files.as_mut()?.errors),
+            Screen::Deleted => Some(&mut self.files.as_mut()?.deleted),
+            Screen::Log => Some(&mut self.logs_app),
+            _ => None,
         }
     }
 
+    /// Returns whether files are shown as tree on current screen
+    fn is_tree(&self) -> bool {
+        self.is_view(FilesView::Tree)
+    }
+
+    /// Returns whether files are shown as treemap on current screen
+    fn is_treemap(&self) -> bool {
+        self.is_view(FilesView::Treemap)
+    }
+
+    fn is_view(&self, view: FilesView) -> bool {
+        self.screen == Screen::Files && self.files.as_ref().map(|f| f.view == view).unwrap_or(false)
+    }
+
+    /// Returns index of highlighted tab in `tab_titles`
+    pub fn selected_tab(&self) -> usize {
+        let action = self.dialog_tab.unwrap_or(match self.screen {
+            // deleted files are part of files list
+            Screen::Files | Screen::Deleted => Action::Files,
+            Screen::Help => Action::Help,
+            Screen::Errors => Action::Errors,
+            Screen::Log => Action::Log,
+        });
+        self.tab_titles()
+            .iter()
+            .position(|(_, a)| *a == action)
+            .unwrap_or(0)
+    }
+
     pub fn start_scan(&mut self, path: String) {
-        self.files = Some(FilesApp::new_scan(path));
+        self.recent_roots.push(path.clone());
+        self.files = Some(FilesApp::new_scan(
+            self.scanner_builder.clone(),
+            path,
+            self.sort,
+        ));
         self.screen = Screen::Files;
     }
 
-    pub fn tab_titles(&self) -> Vec<String> {
+    /// Returns titles of tabs and actions that are performed when they are clicked
+    pub fn tab_titles(&self) -> Vec<(String, Action)> {
         let mut titles = if let Some(files) = &self.files {
-            vec![files.tab_title()]
+            vec![(files.tab_title(), Action::Files)]
         } else {
             vec![]
         };
-        titles.append(&mut vec!["Help".into(), "New scan".into()]);
+        titles.push(("Help".into(), Action::Help));
+        titles.push(("New scan".into(), Action::NewScan));
         if self.screen == Screen::Files {
-            titles.push("Delete".into());
-            titles.push("Rescan".into());
-            titles.push("Scan stats".into());
+            titles.push(("Delete".into(), Action::Delete));
+            titles.push(("Rescan".into(), Action::Rescan));
+            titles.push(("Scan stats".into(), Action::Stats));
+        }
+        if self.files.is_some() {
+            titles.push(("Errors".into(), Action::Errors));
         }
-        titles.push("Log".into());
-        titles.push("Quit".into());
+        titles.push(("Log".into(), Action::Log));
+        titles.push(("Quit".into(), Action::Quit));
         titles
     }
 }
@@ -309,108 +1153,279 @@ impl InputHandler for App {
     fn on_backspace(&mut self) {
         if self.screen == Screen::Files {
             self.files.as_mut().map(FilesApp::go_up);
+        } else if self.screen == Screen::Deleted {
+            self.screen = Screen::Files;
         }
     }
 
     fn on_down(&mut self) {
-        if self.screen == Screen::Files {
+        if self.is_treemap() {
+            self.files.as_mut().unwrap().move_selection(Move::Down);
+        } else if self.screen == Screen::Files {
             self.files.as_mut().map(FilesApp::select_down);
-        } else if self.screen == Screen::Log {
-            //todo refactor input handler so there is no if-else
-            self.logs_app.on_down();
+        } else if let Some(list) = self.get_list() {
+            list.on_down();
         }
     }
 
     fn on_end(&mut self) {
-        if self.screen == Screen::Log {
-            self.logs_app.on_end();
+        if let Some(list) = self.get_list() {
+            list.on_end();
         }
     }
 
     fn on_enter(&mut self) {
         if self.screen == Screen::Files {
-            self.files.as_mut().map(FilesApp::open_selected);
+            let files = self.files.as_mut().unwrap();
+            if files.is_deleted_selected() {
+                self.screen = Screen::Deleted;
+            } else {
+                files.open_selected();
+            }
+        } else if self.screen == Screen::Errors {
+            // show selected directory in files list
+            let files = self.files.as_mut().unwrap();
+            if let Some((path, _)) = files.errors.get_selected().cloned() {
+                files.reveal(&path);
+                self.screen = Screen::Files;
+            }
         }
     }
 
-    fn on_esc(&mut self) {
-        self.on_backspace();
-    }
-
-    fn on_fn(&mut self, n: u8) {
-        match n {
-            1 => self.screen = Screen::Help,
-            5 if self.screen == Screen::Files => self.files.as_mut().unwrap().rescan(true),
-            _ => {}
+    fn on_home(&mut self) {
+        if let Some(list) = self.get_list() {
+            list.on_home();
         }
     }
 
-    fn on_home(&mut self) {
-        if self.screen == Screen::Log {
-            self.logs_app.on_home();
+    fn on_left(&mut self) {
+        if self.is_treemap() {
+            self.files.as_mut().unwrap().move_selection(Move::Left);
+        } else if self.is_tree() {
+            self.files.as_mut().unwrap().collapse_selected();
+        } else {
+            self.on_backspace();
         }
     }
 
-    fn on_key(&mut self, c: char) {
-        match c {
-            'd' if self.screen == Screen::Files => {
-                if let Some(entry) = self.files.as_ref().unwrap().get_selected() {
-                    let mut path = self.files.as_ref().unwrap().current_path.clone();
-                    path.join(entry.get_name().to_string());
-                    self.dialog = Some(Box::new(DeleteDialog::new(path, entry.get_size())));
-                    self.dialog_menu = Some(2);
-                }
-            }
-            'f' if self.files.is_some() => self.screen = Screen::Files,
-            'h' => self.screen = Screen::Help,
-            'l' => {
-                // follow only if log screen was not opened yet
-                self.logs_app
-                    .list_state
-                    .set_follow(self.screen != Screen::Log);
-                self.screen = Screen::Log;
-            }
-            'n' => {
-                self.dialog = Some(Box::new(NewScanDialog::new(
-                    diskscan::get_available_mounts(),
-                )));
-                self.dialog_menu = Some(1);
-            }
-            'r' if self.screen == Screen::Files => self.files.as_mut().unwrap().rescan(true),
-            'q' => self.should_quit = true,
-            's' if self.screen == Screen::Files => {
-                self.dialog = Some(Box::new(ScanStatsDialog::new()));
-                self.dialog_menu = Some(4);
-            }
+    fn on_mouse(&mut self, event: MouseEvent) {
+        match event.kind {
+            MouseEventKind::Down(MouseButton::Left) => self.on_click(event.column, event.row),
+            MouseEventKind::ScrollDown => self.on_down(),
+            MouseEventKind::ScrollUp => self.on_up(),
             _ => {}
         }
     }
 
-    fn on_left(&mut self) {
-        self.on_backspace();
-    }
-
     fn on_page_down(&mut self) {
-        if self.screen == Screen::Log {
-            self.logs_app.on_page_down();
+        if let Some(list) = self.get_list() {
+            list.on_page_down();
         }
     }
 
     fn on_page_up(&mut self) {
-        if self.screen == Screen::Log {
-            self.logs_app.on_page_up();
+        if let Some(list) = self.get_list() {
+            list.on_page_up();
+        }
+    }
+
+    fn on_press(&mut self, event: KeyEvent) {
+        if let Some(action) = self.keymap.get_action(event) {
+            self.on_action(action);
         }
     }
 
     fn on_right(&mut self) {
-        self.on_enter();
+        if self.is_treemap() {
+            self.files.as_mut().unwrap().move_selection(Move::Right);
+        } else if self.is_tree() && !self.files.as_ref().unwrap().is_deleted_selected() {
+            self.files.as_mut().unwrap().expand_selected();
+        } else {
+            self.on_enter();
+        }
     }
 
     fn on_up(&mut self) {
-        if self.screen == Screen::Files {
+        if self.is_treemap() {
+            self.files.as_mut().unwrap().move_selection(Move::Up);
+        } else if self.screen == Screen::Files {
             self.files.as_mut().map(FilesApp::select_up);
-        } else if self.screen == Screen::Log {
-            self.logs_app.on_up();
+        } else if let Some(list) = self.get_list() {
+            list.on_up();
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use std::time::{Duration, Instant};
+
+    use diskscan::{EntryPath, ScannerBuilder, SortOrder};
+    use ratatui::layout::Rect;
+    use tempfile::TempDir;
+
+    use crate::app::{App, FilesApp, ListApp, Screen};
+    use crate::keymap::{Action, Keymap};
+    use crate::term::InputHandler;
+
+    /// Creates temp directory with given subdirectories, it is removed when dropped
+    fn temp_tree(dirs: &[&str]) -> TempDir {
+        let root = tempfile::tempdir().unwrap();
+        for dir in dirs {
+            std::fs::create_dir_all(root.path().join(dir)).unwrap();
+        }
+        root
+    }
+
+    /// Scans given directory and waits until scan is finished
+    fn scan(dir: &Path) -> FilesApp {
+        let files = FilesApp::new_scan(
+            ScannerBuilder::default(),
+            dir.to_str().unwrap().to_string(),
+            SortOrder::default(),
+        );
+        let start = Instant::now();
+        while files.scanner.is_scanning() {
+            assert!(
+                start.elapsed() < Duration::from_secs(10),
+                "scan is too long"
+            );
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        files
+    }
+
+    fn child(parent: &EntryPath, name: &str) -> EntryPath {
+        let mut path = parent.clone();
+        path.join(name.to_string());
+        path
+    }
+
+    #[test]
+    fn go_to() {
+        let dir = temp_tree(&["a/b", "c"]);
+        let mut files = scan(dir.path());
+        let root = files.current_path.clone();
+        let text = |path: &str| format!("{}{}", dir.path().display(), path);
+
+        // trailing separator is ignored
+        files.go_to(&text("/a/b/")).unwrap();
+        assert_eq!(files.current_path, child(&child(&root, "a"), "b"));
+        files.go_to(&text("/a/b/../../c/.")).unwrap();
+        assert_eq!(files.current_path, child(&root, "c"));
+        files.go_to(&text("/a/..")).unwrap();
+        assert_eq!(files.current_path, root);
+
+        let error = files.go_to(&text("/..")).unwrap_err().to_string();
+        assert!(error.contains("outside of scanned directory"), "{}", error);
+        let error = files.go_to(&text("/a/missing")).unwrap_err().to_string();
+        assert!(error.contains("does not exist"), "{}", error);
+        // failed attempts don't change opened directory
+        assert_eq!(files.current_path, root);
+    }
+
+    #[test]
+    fn history() {
+        let dir = temp_tree(&["a/b", "c"]);
+        let mut files = scan(dir.path());
+        let root = files.current_path.clone();
+        let a = child(&root, "a");
+        let b = child(&a, "b");
+
+        files.open_path(a.clone());
+        files.open_path(b.clone());
+        files.go_back();
+        files.go_back();
+        assert_eq!(files.current_path, root);
+        files.go_forward();
+        assert_eq!(files.current_path, a);
+        assert_eq!(files.forward, [b]);
+
+        // opening other directory after going back drops forward history
+        files.open_path(child(&root, "c"));
+        assert!(files.forward.is_empty());
+        files.go_forward();
+        assert_eq!(files.current_path, child(&root, "c"));
+        assert_eq!(files.back, [root.clone(), a.clone()]);
+        files.go_back();
+        assert_eq!(files.current_path, a);
+
+        // opening the same directory is not remembered
+        files.open_path(a.clone());
+        assert_eq!(files.back, [root]);
+    }
+
+    #[test]
+    fn reveal_root() {
+        let dir = temp_tree(&["child"]);
+        let mut files = FilesApp::new_scan(
+            ScannerBuilder::default(),
+            dir.path().to_str().unwrap().to_string(),
+            SortOrder::default(),
+        );
+        let root = files.current_path.clone();
+        let mut child = root.clone();
+        child.join("child".to_string());
+        files.open_path(child.clone());
+
+        // root has no parent, so it is opened
+        files.reveal(&root);
+        assert_eq!(files.current_path, root);
+
+        files.reveal(&child);
+        assert_eq!(files.current_path, root);
+    }
+
+    #[test]
+    fn list_item_at() {
+        let mut list = ListApp {
+            entries: (0..10).collect(),
+            ..ListApp::default()
+        };
+        list.select(0);
+        list.set_area(Rect::new(1, 1, 20, 4));
+        assert_eq!(list.item_at(1, 1), Some(0));
+        assert_eq!(list.item_at(5, 4), Some(3));
+        assert_eq!(list.item_at(5, 5), None);
+        assert_eq!(list.item_at(0, 1), None);
+
+        // list is scrolled so selected entry is at the bottom
+        list.on_end();
+        list.set_area(Rect::new(1, 1, 20, 4));
+        assert_eq!(list.item_at(1, 1), Some(6));
+        assert!(list.select_at(1, 2));
+        assert_eq!(list.get_selected(), Some(&7));
+
+        // scrolling up keeps offset until selected entry is above it
+        list.on_up();
+        list.on_up();
+        list.set_area(Rect::new(1, 1, 20, 4));
+        assert_eq!(list.item_at(1, 1), Some(5));
+        assert!(!list.select_at(1, 5));
+    }
+
+    #[test]
+    fn selected_tab() {
+        let mut app = App::new(ScannerBuilder::default(), Keymap::default());
+        let selected = |app: &App| app.tab_titles()[app.selected_tab()].1;
+        assert_eq!(selected(&app), Action::Help);
+        app.screen = Screen::Log;
+        assert_eq!(selected(&app), Action::Log);
+        app.dialog_tab = Some(Action::NewScan);
+        assert_eq!(selected(&app), Action::NewScan);
+
+        let dir = temp_tree(&[]);
+        app.start_scan(dir.path().to_str().unwrap().to_string());
+        app.dialog_tab = None;
+        assert_eq!(selected(&app), Action::Files);
+        // tabs of files screen are added before errors and log
+        app.dialog_tab = Some(Action::Stats);
+        assert_eq!(selected(&app), Action::Stats);
+        app.dialog_tab = None;
+        app.screen = Screen::Errors;
+        assert_eq!(selected(&app), Action::Errors);
+        app.screen = Screen::Deleted;
+        assert_eq!(selected(&app), Action::Files);
+    }
+}
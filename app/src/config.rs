@@ -0,0 +1,270 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use serde::de::{self, Deserializer};
+use serde::Deserialize;
+
+use diskscan::config::{duration, list, OneOrMany};
+use diskscan::{ScannerConfig, SortOrder};
+
+use crate::file_list::Column;
+use crate::units::{Units, MAX_PRECISION};
+
+/// Preferences loaded from config file, `None` if value is not set in file
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Colors from `[colors]` table that replace colors of theme
+    #[serde(deserialize_with = "colors")]
+    pub colors: Vec<(String, Vec<String>)>,
+    #[serde(deserialize_with = "parsed_list")]
+    pub columns: Option<Vec<Column>>,
+    pub keymap: Option<String>,
+    #[serde(deserialize_with = "precision")]
+    pub precision: Option<usize>,
+    pub simple_graphics: Option<bool>,
+    #[serde(deserialize_with = "sort")]
+    pub sort: Option<SortOrder>,
+    pub theme: Option<String>,
+    #[serde(deserialize_with = "duration")]
+    pub tick_rate: Option<Duration>,
+    #[serde(deserialize_with = "parsed")]
+    pub units: Option<Units>,
+    /// Options of scanner from `[scanner]` table
+    pub scanner: ScannerConfig,
+}
+
+impl Config {
+    /// Returns path to config file inside user config directory
+    ///
+    /// It is `$XDG_CONFIG_HOME/spacedisplay/config.toml` (or `~/.config/...`),
+    /// and `%APPDATA%\spacedisplay\config.toml` on Windows
+    pub fn default_path() -> Option<PathBuf> {
+        let dir = if cfg!(windows) {
+            std::env::var_os("APPDATA").map(PathBuf::from)
+        } else {
+            std::env::var_os("XDG_CONFIG_HOME")
+                .filter(|dir| !dir.is_empty())
+                .map(PathBuf::from)
+                .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
+        }?;
+        Some(dir.join("spacedisplay").join("config.toml"))
+    }
+
+    /// Loads config from given file or from default location
+    ///
+    /// Missing file at default location is not an error, default config is returned
+    pub fn load(path: Option<&Path>) -> Result<Config> {
+        let (path, required) = match path {
+            Some(path) => (path.to_path_buf(), true),
+            None => match Config::default_path() {
+                Some(path) => (path, false),
+                None => return Ok(Config::default()),
+            },
+        };
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if !required && e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Config::default())
+            }
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("failed to read config '{}'", path.display()))
+            }
+        };
+        Config::parse(&text).with_context(|| format!("invalid config '{}'", path.display()))
+    }
+
+    pub fn parse(text: &str) -> Result<Config> {
+        Ok(toml::from_str(text)?)
+    }
+}
+
+pub fn parse_sort(s: &str) -> Result<SortOrder> {
+    match s {
+        "size" => Ok(SortOrder::Size),
+        "name" => Ok(SortOrder::Name),
+        _ => bail!("unknown sort order '{}', available: size, name", s),
+    }
+}
+
+fn colors<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<(String, Vec<String>)>, D::Error> {
+    let colors = BTreeMap::<String, OneOrMany>::deserialize(d)?;
+    Ok(colors
+        .into_iter()
+        .map(|(name, values)| (name, values.into()))
+        .collect())
+}
+
+fn parsed<'de, D, T>(d: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    let s = String::deserialize(d)?;
+    s.parse().map(Some).map_err(de::Error::custom)
+}
+
+fn parsed_list<'de, D, T>(d: D) -> Result<Option<Vec<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    list(d)?
+        .iter()
+        .map(|s| s.parse().map_err(de::Error::custom))
+        .collect::<Result<_, _>>()
+        .map(Some)
+}
+
+fn precision<'de, D: Deserializer<'de>>(d: D) -> Result<Option<usize>, D::Error> {
+    match usize::deserialize(d)? {
+        p if p > MAX_PRECISION => Err(de::Error::custom(format!(
+            "must be between 0 and {}",
+            MAX_PRECISION
+        ))),
+        p => Ok(Some(p)),
+    }
+}
+
+fn sort<'de, D: Deserializer<'de>>(d: D) -> Result<Option<SortOrder>, D::Error> {
+    let s = String::deserialize(d)?;
+    parse_sort(&s).map(Some).map_err(de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns error message without source snippet
+    fn parse_error(text: &str) -> String {
+        format!("{:#}", Config::parse(text).unwrap_err())
+    }
+
+    #[test]
+    fn empty() {
+        let config = Config::parse("").unwrap();
+        assert!(config.colors.is_empty());
+        assert_eq!(config.tick_rate, None);
+        assert_eq!(config.scanner.one_file_system, None);
+    }
+
+    #[test]
+    fn all_keys() {
+        let config = Config::parse(
+            r##"
+            tick_rate = 200
+            simple_graphics = true
+            keymap = "vi"
+            theme = "light"
+            units = "decimal"
+            precision = 2
+            sort = "name"
+            columns = ["size", "bar"]
+
+            [scanner]
+            one_file_system = false
+            cross = "/home"
+            real_fs = ["fuse.sshfs"]
+            pseudo_fs = []
+            skip_snapshots = true
+            exclude = [".git", "/var/cache"]
+            poll_interval = 5000
+            rescan_delay = 300
+
+            [colors]
+            accent = "#ffaf00"
+            palette = ["blue", "green"]
+            "##,
+        )
+        .unwrap();
+        assert_eq!(config.tick_rate, Some(Duration::from_millis(200)));
+        assert_eq!(config.simple_graphics, Some(true));
+        assert_eq!(config.keymap.as_deref(), Some("vi"));
+        assert_eq!(config.theme.as_deref(), Some("light"));
+        assert_eq!(config.units, Some(Units::Decimal));
+        assert_eq!(config.precision, Some(2));
+        assert_eq!(config.sort, Some(SortOrder::Name));
+        assert_eq!(config.columns, Some(vec![Column::Size, Column::Bar]));
+
+        let scanner = config.scanner;
+        assert_eq!(scanner.one_file_system, Some(false));
+        // single string is a list with one element
+        assert_eq!(scanner.cross, ["/home"]);
+        assert_eq!(scanner.real_fs, ["fuse.sshfs"]);
+        assert!(scanner.pseudo_fs.is_empty());
+        assert_eq!(scanner.skip_snapshots, Some(true));
+        assert_eq!(scanner.exclude, [".git", "/var/cache"]);
+        assert_eq!(scanner.poll_interval, Some(Duration::from_millis(5000)));
+        assert_eq!(scanner.rescan_delay, Some(Duration::from_millis(300)));
+
+        assert_eq!(
+            config.colors,
+            [
+                ("accent".to_string(), vec!["#ffaf00".to_string()]),
+                (
+                    "palette".to_string(),
+                    vec!["blue".to_string(), "green".to_string()]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn dotted_and_quoted_keys() {
+        let config =
+            Config::parse("scanner.exclude = \"a\"\n\"theme\" = 'dark'\ncolors.\"text\" = \"red\"")
+                .unwrap();
+        assert_eq!(config.scanner.exclude, ["a"]);
+        assert_eq!(config.theme.as_deref(), Some("dark"));
+        assert_eq!(config.colors[0].0, "text");
+    }
+
+    #[test]
+    fn unknown_keys() {
+        let error = parse_error("theme = \"dark\"\nfoo = 1");
+        assert!(error.contains("line 2"), "{}", error);
+        assert!(error.contains("unknown field `foo`"), "{}", error);
+
+        let error = parse_error("[scanner]\nexclude = []\ncrossing = true");
+        assert!(error.contains("line 3"), "{}", error);
+        assert!(error.contains("unknown field `crossing`"), "{}", error);
+
+        // scanner keys are not allowed at top level
+        assert!(parse_error("exclude = []").contains("unknown field `exclude`"));
+    }
+
+    #[test]
+    fn type_errors() {
+        let error = parse_error("\n\ntick_rate = \"fast\"");
+        assert!(error.contains("line 3"), "{}", error);
+        assert!(error.contains("expected u64"), "{}", error);
+
+        assert!(parse_error("tick_rate = -1").contains("expected u64"));
+        assert!(parse_error("simple_graphics = 1").contains("expected a boolean"));
+        assert!(parse_error("[scanner]\nexclude = 1").contains("string or array of strings"));
+        assert!(parse_error("[colors]\ntext = [1]").contains("string or array of strings"));
+    }
+
+    #[test]
+    fn invalid_values() {
+        assert!(parse_error("precision = 7").contains("must be between 0 and 6"));
+        assert!(parse_error("sort = \"date\"").contains("unknown sort order 'date'"));
+        assert!(parse_error("units = \"si\"").contains("unknown units 'si'"));
+        assert!(parse_error("columns = [\"size\", \"x\"]").contains("unknown column 'x'"));
+        assert!(parse_error("[scanner]\npoll_interval = 0").contains("at least 100 ms"));
+    }
+
+    #[test]
+    fn duplicate_keys() {
+        let error = parse_error("theme = \"dark\"\ntheme = \"light\"");
+        assert!(error.contains("line 2"), "{}", error);
+        assert!(error.contains("duplicate key"), "{}", error);
+    }
+}
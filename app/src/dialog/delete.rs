@@ -1,7 +1,10 @@
+use std::cell::Cell;
+
 use byte_unit::Byte;
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
 use ratatui::buffer::Buffer;
 use ratatui::layout::{Alignment, Rect};
-use ratatui::style::{Color, Modifier, Style};
+use ratatui::style::{Modifier, Style};
 use ratatui::text::{Span, Spans};
 use ratatui::widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget};
 use unicode_width::UnicodeWidthStr;
@@ -19,6 +22,8 @@ pub struct DeleteDialog {
     selected_yes: bool,
     chosen: Option<bool>,
     should_close: bool,
+    /// Areas of "Yes" and "No" buttons during last render
+    buttons: Cell<(Rect, Rect)>,
 }
 
 impl DeleteDialog {
@@ -31,6 +36,7 @@ impl DeleteDialog {
             selected_yes: false,
             chosen: None,
             should_close: false,
+            buttons: Cell::new((Rect::default(), Rect::default())),
         }
     }
 
@@ -66,23 +72,35 @@ impl InputHandler for DeleteDialog {
         self.selected_yes = true;
     }
 
+    fn on_mouse(&mut self, event: MouseEvent) {
+        if event.kind == MouseEventKind::Down(MouseButton::Left) {
+            let (yes, no) = self.buttons.get();
+            if utils::contains(yes, event.column, event.row) {
+                self.chosen = Some(true);
+            } else if utils::contains(no, event.column, event.row) {
+                self.should_close = true;
+            }
+        }
+    }
+
     fn on_right(&mut self) {
         self.selected_yes = false;
     }
 }
 
 impl Dialog for DeleteDialog {
-    fn get_widget<'a>(&'a self, app: &'a App) -> DialogWidget<'_> {
+    fn get_widget<'a>(&'a self, app: &'a App) -> DialogWidget<'a> {
         DialogWidget(self, app)
     }
 
-    fn render(&self, _: &App, area: Rect, buf: &mut Buffer) {
+    fn render(&self, app: &App, area: Rect, buf: &mut Buffer) {
+        let theme = &app.theme;
         Clear.render(area, buf);
-        buf.set_style(area, Style::default().bg(Color::Black));
+        buf.set_style(area, Style::default().bg(theme.background));
 
         let block = Block::default()
             .borders(Borders::ALL)
-            .style(Style::default().fg(Color::White))
+            .style(Style::default().fg(theme.text))
             .title(Self::TITLE)
             .border_type(BorderType::Plain);
         Widget::render(block, area, buf);
@@ -93,30 +111,21 @@ impl Dialog for DeleteDialog {
         }
 
         let y = area.y + area.height.saturating_sub(2);
-        let (yes_fg, yes_bg, no_fg, no_bg) = if self.selected_yes {
-            (Color::Black, Color::Gray, Color::White, Color::Black)
+        // selected button is reversed, so it is visible without colors too
+        let normal = Style::default().fg(theme.text).bg(theme.background);
+        let selected = normal.add_modifier(Modifier::REVERSED);
+        let (yes, no) = if self.selected_yes {
+            (selected, normal)
         } else {
-            (Color::White, Color::Black, Color::Black, Color::Gray)
+            (normal, selected)
         };
 
         let text = vec![Spans::from(vec![
-            Span::styled(
-                "Y",
-                Style::default()
-                    .fg(yes_fg)
-                    .bg(yes_bg)
-                    .add_modifier(Modifier::UNDERLINED),
-            ),
-            Span::styled("es", Style::default().fg(yes_fg).bg(yes_bg)),
+            Span::styled("Y", yes.add_modifier(Modifier::UNDERLINED)),
+            Span::styled("es", yes),
             Span::raw("   "),
-            Span::styled(
-                "N",
-                Style::default()
-                    .fg(no_fg)
-                    .bg(no_bg)
-                    .add_modifier(Modifier::UNDERLINED),
-            ),
-            Span::styled("o", Style::default().fg(no_fg).bg(no_bg)),
+            Span::styled("N", no.add_modifier(Modifier::UNDERLINED)),
+            Span::styled("o", no),
         ])];
         let p = Paragraph::new(text).alignment(Alignment::Center);
         let area = Rect {
@@ -125,6 +134,12 @@ impl Dialog for DeleteDialog {
             width: area.width,
             height: 1,
         };
+        // buttons take 8 cells ("Yes   No") in the center
+        let x = area.x + area.width.saturating_sub(8) / 2;
+        self.buttons.set((
+            Rect::new(x, y, 3, 1).intersection(area),
+            Rect::new(x + 6, y, 2, 1).intersection(area),
+        ));
         p.render(area, buf);
     }
 
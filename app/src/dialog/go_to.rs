@@ -0,0 +1,161 @@
+use std::path::{Path, MAIN_SEPARATOR};
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Span, Spans};
+use ratatui::widgets::{Block, BorderType, Borders, Clear, Widget};
+use unicode_width::UnicodeWidthStr;
+
+use crate::app::App;
+use crate::dialog::{Dialog, DialogWidget};
+use crate::path_input::PathInput;
+use crate::term::InputHandler;
+use crate::theme::Theme;
+
+const HINT: &str = "Tab completes path, Up/Down browse history";
+const INPUT_LABEL: &str = " Path: ";
+
+/// Opens any directory inside of scanned one or reveals file
+pub struct GoToDialog {
+    input: PathInput,
+    /// Error of last attempt, dialog stays open until valid path is entered
+    error: Option<String>,
+    chosen: Option<String>,
+    should_close: bool,
+}
+
+impl GoToDialog {
+    const TITLE: &'static str = "Go to path ";
+
+    /// Creates dialog with input filled with opened directory
+    ///
+    /// History contains previously opened directories, most recent last
+    pub fn new(current: String, history: Vec<String>) -> Self {
+        let mut input = PathInput::new(history);
+        let text = if current.ends_with(std::path::is_separator) {
+            current
+        } else {
+            format!("{}{}", current, MAIN_SEPARATOR)
+        };
+        input.set_text(text);
+        Self {
+            input,
+            error: None,
+            chosen: None,
+            should_close: false,
+        }
+    }
+
+    fn create_input_line(&self, theme: &Theme) -> Spans<'_> {
+        let color = if Path::new(self.input.text()).exists() {
+            theme.accent
+        } else {
+            theme.error
+        };
+        Spans::from(vec![
+            Span::styled(INPUT_LABEL, Style::default().fg(theme.text)),
+            Span::styled(self.input.text(), Style::default().fg(color)),
+            Span::styled(" ", Style::default().add_modifier(Modifier::REVERSED)),
+        ])
+    }
+
+    fn create_status_line(&self, theme: &Theme) -> Spans<'_> {
+        match &self.error {
+            Some(error) => Spans::from(Span::styled(
+                format!(" {}", error),
+                Style::default().fg(theme.error),
+            )),
+            None => Spans::from(Span::styled(
+                format!(" {}", HINT),
+                Style::default().fg(theme.muted),
+            )),
+        }
+    }
+}
+
+impl InputHandler for GoToDialog {
+    fn on_backspace(&mut self) {
+        self.input.backspace();
+        self.error = None;
+    }
+
+    fn on_down(&mut self) {
+        self.input.history_next();
+    }
+
+    fn on_enter(&mut self) {
+        self.chosen = Some(self.input.text().to_string());
+    }
+
+    fn on_esc(&mut self) {
+        self.should_close = true;
+    }
+
+    fn on_key(&mut self, c: char) {
+        self.input.insert(c);
+        self.error = None;
+    }
+
+    fn on_tab(&mut self) {
+        self.input.complete();
+    }
+
+    fn on_up(&mut self) {
+        self.input.history_prev();
+    }
+}
+
+impl Dialog for GoToDialog {
+    fn get_widget<'a>(&'a self, app: &'a App) -> DialogWidget<'a> {
+        DialogWidget(self, app)
+    }
+
+    fn render(&self, app: &App, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+        buf.set_style(area, Style::default().bg(app.theme.background));
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .style(Style::default().fg(app.theme.text))
+            .title(Self::TITLE)
+            .border_type(BorderType::Plain);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let lines = [
+            self.create_input_line(&app.theme),
+            self.create_status_line(&app.theme),
+        ];
+        for (i, line) in lines.iter().enumerate().take(inner.height as usize) {
+            buf.set_spans(inner.x, inner.y + i as u16, line, inner.width);
+        }
+    }
+
+    fn size(&self, _: &App) -> (u16, u16) {
+        let max_width = [
+            Self::TITLE.width(),
+            INPUT_LABEL.width() + self.input.text().width() + 1,
+            1 + self.error.as_deref().unwrap_or(HINT).width(),
+        ]
+        .into_iter()
+        .max()
+        .unwrap_or(0);
+        (3 + max_width as u16, 4)
+    }
+
+    fn try_finish(mut self: Box<Self>, app: &mut App) -> Result<(), Box<dyn Dialog>> {
+        if let Some(path) = self.chosen.take() {
+            match app.files.as_mut().map(|files| files.go_to(&path)) {
+                Some(Err(e)) => self.error = Some(format!("{:#}", e)),
+                _ => return Ok(()),
+            }
+        }
+
+        if self.should_close {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+}
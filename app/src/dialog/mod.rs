@@ -1,20 +1,25 @@
+use crossterm::event::MouseEvent;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
 use ratatui::widgets::Widget;
 
 pub use delete::DeleteDialog;
+pub use go_to::GoToDialog;
 pub use new_scan::NewScanDialog;
 pub use scan_stats::ScanStatsDialog;
+pub use used_space::UsedSpaceDialog;
 
 use crate::app::App;
 use crate::term::InputHandler;
 
 mod delete;
+mod go_to;
 mod new_scan;
 mod scan_stats;
+mod used_space;
 
 pub trait Dialog: InputHandler {
-    fn get_widget<'a>(&'a self, app: &'a App) -> DialogWidget<'_>;
+    fn get_widget<'a>(&'a self, app: &'a App) -> DialogWidget<'a>;
 
     fn render(&self, app: &App, area: Rect, buf: &mut Buffer);
 
@@ -39,6 +44,10 @@ impl InputHandler for Box<dyn Dialog> {
         self.as_mut().on_down();
     }
 
+    fn on_end(&mut self) {
+        self.as_mut().on_end();
+    }
+
     fn on_enter(&mut self) {
         self.as_mut().on_enter();
     }
@@ -51,6 +60,10 @@ impl InputHandler for Box<dyn Dialog> {
         self.as_mut().on_fn(n);
     }
 
+    fn on_home(&mut self) {
+        self.as_mut().on_home();
+    }
+
     fn on_key(&mut self, c: char) {
         self.as_mut().on_key(c);
     }
@@ -59,10 +72,26 @@ impl InputHandler for Box<dyn Dialog> {
         self.as_mut().on_left();
     }
 
+    fn on_mouse(&mut self, event: MouseEvent) {
+        self.as_mut().on_mouse(event);
+    }
+
+    fn on_page_down(&mut self) {
+        self.as_mut().on_page_down();
+    }
+
+    fn on_page_up(&mut self) {
+        self.as_mut().on_page_up();
+    }
+
     fn on_right(&mut self) {
         self.as_mut().on_right();
     }
 
+    fn on_tab(&mut self) {
+        self.as_mut().on_tab();
+    }
+
     fn on_up(&mut self) {
         self.as_mut().on_up();
     }
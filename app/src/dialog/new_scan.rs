@@ -1,103 +1,452 @@
+use std::sync::{Arc, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use byte_unit::Byte;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
-use ratatui::style::{Color, Modifier, Style};
-use ratatui::text::Spans;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Span, Spans};
 use ratatui::widgets::{
     Block, BorderType, Borders, Clear, List, ListItem, ListState, StatefulWidget, Widget,
 };
 use unicode_width::UnicodeWidthStr;
 
+use diskscan::{MountInfo, MountStats};
+
 use crate::app::App;
 use crate::dialog::{Dialog, DialogWidget};
+use crate::path_input::PathInput;
 use crate::term::InputHandler;
+use crate::theme::Theme;
+use crate::utils;
+
+/// Width of usage bar in cells
+const BAR_WIDTH: usize = 10;
+
+/// Max width of device column, longer devices are truncated
+const MAX_DEVICE_WIDTH: usize = 24;
+
+/// Usage ratio after which mount is highlighted as almost full
+const FULL_RATIO: f64 = 0.9;
+
+/// Time after which mount that still has no stats is shown without them
+const STATS_TIMEOUT: Duration = Duration::from_secs(2);
+
+const LOADING: &str = "…";
+
+const NO_MOUNTS: &str = "No mounts, press 'P' or 'R' to show hidden";
+
+const INPUT_HINT: &str = "Press Tab or '/' to enter any path";
+const INPUT_LABEL: &str = " Path: ";
+
+struct MountRow {
+    info: MountInfo,
+    /// Filled by worker thread since stats of network mount can take forever
+    stats: Arc<OnceLock<Option<MountStats>>>,
+    requested_at: Option<Instant>,
+}
+
+impl MountRow {
+    fn new(info: MountInfo) -> Self {
+        MountRow {
+            info,
+            stats: Arc::new(OnceLock::new()),
+            requested_at: None,
+        }
+    }
+
+    fn available(&self) -> String {
+        if self.is_loading() {
+            return LOADING.to_string();
+        }
+        self.stats()
+            .map(|s| format!("{} available", utils::byte_to_str(s.available, 0)))
+            .unwrap_or_default()
+    }
+
+    fn device(&self) -> String {
+        if self.info.device.chars().count() > MAX_DEVICE_WIDTH {
+            let mut device: String = self
+                .info
+                .device
+                .chars()
+                .take(MAX_DEVICE_WIDTH - 1)
+                .collect();
+            device.push('…');
+            device
+        } else {
+            self.info.device.clone()
+        }
+    }
+
+    /// Whether stats are requested but not received yet
+    fn is_loading(&self) -> bool {
+        self.stats.get().is_none()
+            && self
+                .requested_at
+                .is_some_and(|time| time.elapsed() < STATS_TIMEOUT)
+    }
+
+    /// Starts loading of stats in background if it is not started yet
+    fn request_stats(&mut self) {
+        if self.requested_at.is_some() {
+            return;
+        }
+        self.requested_at = Some(Instant::now());
+        let stats = self.stats.clone();
+        let path = self.info.path.clone();
+        // thread is detached, so hung mount doesn't block anything except itself
+        let _ = thread::Builder::new()
+            .name("mount-stats".to_string())
+            .spawn(move || {
+                let _ = stats.set(diskscan::get_mount_stats(path));
+            });
+    }
+
+    fn stats(&self) -> Option<&MountStats> {
+        self.stats.get()?.as_ref()
+    }
+
+    /// Returns used part of mount or `None` if mount has no size
+    fn usage(&self) -> Option<f64> {
+        let stats = self.stats()?;
+        let total = stats.total.get_bytes();
+        if total == 0 {
+            return None;
+        }
+        let used = total.saturating_sub(stats.free.get_bytes());
+        Some(used as f64 / total as f64)
+    }
+
+    fn used(&self) -> String {
+        if self.is_loading() {
+            return LOADING.to_string();
+        }
+        self.stats()
+            .map(|s| {
+                let used = Byte::from_bytes(s.total.get_bytes().saturating_sub(s.free.get_bytes()));
+                format!(
+                    "{} / {}",
+                    utils::byte_to_str(used, 0),
+                    utils::byte_to_str(s.total, 0)
+                )
+            })
+            .unwrap_or_default()
+    }
+}
 
 pub struct NewScanDialog {
-    mounts: Vec<String>,
+    mounts: Vec<MountRow>,
+    /// Indices of mounts that are shown in list
+    visible: Vec<usize>,
+    show_pseudo: bool,
+    show_read_only: bool,
+    /// Recently scanned paths, most recent last
+    history: Vec<String>,
+    /// Set while user enters path
+    input: Option<PathInput>,
     selected: usize,
-    chosen: Option<usize>,
+    chosen: Option<String>,
     should_close: bool,
 }
 
 impl NewScanDialog {
     const TITLE: &'static str = "New Scan ";
 
-    pub fn new(mounts: Vec<String>) -> Self {
-        Self {
-            mounts,
+    pub fn new(mounts: Vec<MountInfo>, history: Vec<String>) -> Self {
+        let mut dialog = Self {
+            mounts: mounts.into_iter().map(MountRow::new).collect(),
+            visible: vec![],
+            show_pseudo: false,
+            show_read_only: true,
+            history,
+            input: None,
             selected: 0,
             chosen: None,
             should_close: false,
+        };
+        dialog.update_visible();
+        dialog
+    }
+
+    /// Returns width of each column in rows
+    fn column_widths(&self) -> [usize; 5] {
+        let mut widths = [0; 5];
+        for row in self.visible.iter().map(|&i| &self.mounts[i]) {
+            let columns = [
+                row.info.path.width(),
+                row.info.fs_type.width(),
+                row.device().width(),
+                row.used().width(),
+                row.available().width(),
+            ];
+            for (width, column) in widths.iter_mut().zip(columns) {
+                *width = (*width).max(column);
+            }
         }
+        widths
+    }
+
+    fn create_item(&self, row: &MountRow, widths: &[usize; 5], theme: &Theme) -> ListItem<'static> {
+        let text = Style::default().fg(theme.text);
+        let mut spans = vec![
+            Span::styled(format!("{:<1$}  ", row.info.path, widths[0]), text),
+            Span::styled(
+                format!("{:<1$}  ", row.info.fs_type, widths[1]),
+                Style::default().fg(theme.file),
+            ),
+            Span::styled(
+                format!("{:<1$}  ", row.device(), widths[2]),
+                Style::default().fg(theme.muted),
+            ),
+        ];
+        // usage bar is drawn with reversed spaces so it looks the same with simple graphics
+        // and without colors
+        let usage = row.usage().unwrap_or(0.0);
+        let filled = ((usage * BAR_WIDTH as f64).round() as usize).min(BAR_WIDTH);
+        let color = if usage >= FULL_RATIO {
+            theme.error
+        } else {
+            theme.accent
+        };
+        spans.push(Span::styled(
+            " ".repeat(filled),
+            Style::default().fg(color).add_modifier(Modifier::REVERSED),
+        ));
+        spans.push(Span::styled(
+            " ".repeat(BAR_WIDTH - filled),
+            Style::default().bg(theme.dim),
+        ));
+        spans.push(Span::styled(
+            format!("  {:>1$}  ", row.used(), widths[3]),
+            Style::default().fg(theme.accent),
+        ));
+        spans.push(Span::styled(
+            format!("{:>1$}", row.available(), widths[4]),
+            Style::default().fg(theme.free),
+        ));
+        ListItem::new(Spans::from(spans))
+    }
+
+    /// Returns index of visible mount with the highest usage
+    fn fullest(&self) -> Option<usize> {
+        self.visible
+            .iter()
+            .map(|&i| self.mounts[i].usage())
+            .enumerate()
+            .filter_map(|(pos, usage)| Some((pos, usage?)))
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(pos, _)| pos)
+    }
+
+    fn create_input_line(&self, theme: &Theme) -> Spans<'_> {
+        match &self.input {
+            Some(input) => {
+                let color = if input.path().is_some() {
+                    theme.accent
+                } else {
+                    theme.error
+                };
+                Spans::from(vec![
+                    Span::styled(INPUT_LABEL, Style::default().fg(theme.text)),
+                    Span::styled(input.text(), Style::default().fg(color)),
+                    Span::styled(" ", Style::default().add_modifier(Modifier::REVERSED)),
+                ])
+            }
+            None => Spans::from(Span::styled(
+                format!("   {}", INPUT_HINT),
+                Style::default().fg(theme.muted),
+            )),
+        }
+    }
+
+    fn start_input(&mut self) {
+        self.input = Some(PathInput::new(self.history.clone()));
+    }
+
+    fn title(&self) -> String {
+        let on_off = |v| if v { "on" } else { "off" };
+        format!(
+            "{}(P: pseudo {}, R: read-only {}, F: scan fullest) ",
+            Self::TITLE,
+            on_off(self.show_pseudo),
+            on_off(self.show_read_only),
+        )
+    }
+
+    fn update_visible(&mut self) {
+        self.visible = self
+            .mounts
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| self.show_pseudo || !row.info.is_pseudo)
+            .filter(|(_, row)| self.show_read_only || !row.info.is_read_only)
+            .map(|(i, _)| i)
+            .collect();
+        // only shown mounts are queried, hidden ones are often pseudo filesystems
+        for &i in &self.visible {
+            self.mounts[i].request_stats();
+        }
+        self.selected = self.selected.min(self.visible.len().saturating_sub(1));
     }
 }
 
 impl InputHandler for NewScanDialog {
+    fn on_backspace(&mut self) {
+        match self.input.as_mut() {
+            Some(input) => input.backspace(),
+            None => self.on_esc(),
+        }
+    }
+
     fn on_down(&mut self) {
-        if self.selected + 1 < self.mounts.len() {
+        if let Some(input) = self.input.as_mut() {
+            input.history_next();
+        } else if self.selected + 1 < self.visible.len() {
             self.selected += 1;
         }
     }
 
     fn on_enter(&mut self) {
-        self.chosen = Some(self.selected);
+        if let Some(input) = self.input.as_ref() {
+            self.chosen = input.path();
+        } else if let Some(&i) = self.visible.get(self.selected) {
+            self.chosen = Some(self.mounts[i].info.path.clone());
+        }
     }
 
     fn on_esc(&mut self) {
-        self.should_close = true;
+        if self.input.is_some() {
+            self.input = None;
+        } else {
+            self.should_close = true;
+        }
     }
 
     fn on_key(&mut self, c: char) {
-        self.should_close = c == 'q' || c == 'n';
+        if let Some(input) = self.input.as_mut() {
+            input.insert(c);
+            return;
+        }
+        match c {
+            '/' => {
+                self.start_input();
+                self.input.as_mut().unwrap().insert(c);
+            }
+            'f' => {
+                if let Some(pos) = self.fullest() {
+                    self.selected = pos;
+                    self.on_enter();
+                }
+            }
+            'p' => {
+                self.show_pseudo = !self.show_pseudo;
+                self.update_visible();
+            }
+            'r' => {
+                self.show_read_only = !self.show_read_only;
+                self.update_visible();
+            }
+            'q' | 'n' => self.should_close = true,
+            _ => {}
+        }
+    }
+
+    fn on_tab(&mut self) {
+        match self.input.as_mut() {
+            Some(input) => input.complete(),
+            None => self.start_input(),
+        }
     }
 
     fn on_up(&mut self) {
-        if self.selected > 0 {
+        if let Some(input) = self.input.as_mut() {
+            input.history_prev();
+        } else if self.selected > 0 {
             self.selected -= 1;
         }
     }
 }
 
 impl Dialog for NewScanDialog {
-    fn get_widget<'a>(&'a self, app: &'a App) -> DialogWidget<'_> {
+    fn get_widget<'a>(&'a self, app: &'a App) -> DialogWidget<'a> {
         DialogWidget(self, app)
     }
 
-    fn render(&self, _: &App, area: Rect, buf: &mut Buffer) {
+    fn render(&self, app: &App, area: Rect, buf: &mut Buffer) {
         Clear.render(area, buf);
-        buf.set_style(area, Style::default().bg(Color::Black));
+        buf.set_style(area, Style::default().bg(app.theme.background));
 
-        let items: Vec<_> = self
-            .mounts
-            .iter()
-            .map(|file| ListItem::new(Spans::from(file.as_str())))
-            .collect();
+        let widths = self.column_widths();
+        let items: Vec<_> = if self.visible.is_empty() {
+            vec![ListItem::new(Spans::from(NO_MOUNTS))]
+        } else {
+            self.visible
+                .iter()
+                .map(|&i| self.create_item(&self.mounts[i], &widths, &app.theme))
+                .collect()
+        };
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .style(Style::default().fg(app.theme.text))
+            .title(self.title())
+            .border_type(BorderType::Plain);
+        let inner = block.inner(area);
+        block.render(area, buf);
+        if inner.height == 0 {
+            return;
+        }
+
+        // first line is used for path input, mounts are listed below
+        buf.set_spans(
+            inner.x,
+            inner.y,
+            &self.create_input_line(&app.theme),
+            inner.width,
+        );
+        let list_area = Rect {
+            y: inner.y + 1,
+            height: inner.height - 1,
+            ..inner
+        };
 
         let list = List::new(items)
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .style(Style::default().fg(Color::White))
-                    .title(Self::TITLE)
-                    .border_type(BorderType::Plain),
-            )
             .highlight_symbol(" > ")
             .highlight_style(Style::default().add_modifier(Modifier::BOLD));
         let mut state = ListState::default();
-        state.select(Some(self.selected));
-        StatefulWidget::render(list, area, buf, &mut state);
+        if !self.visible.is_empty() && self.input.is_none() {
+            state.select(Some(self.selected));
+        }
+        StatefulWidget::render(list, list_area, buf, &mut state);
     }
 
     fn size(&self, _: &App) -> (u16, u16) {
-        let max_width = std::iter::once(Self::TITLE.width())
-            .chain(self.mounts.iter().map(|m| m.width() + 4))
-            .max()
+        // highlight symbol, column separators and usage bar
+        let row_width = self.column_widths().iter().sum::<usize>() + 3 + 2 * 5 + BAR_WIDTH;
+        let input_width = self
+            .input
+            .as_ref()
+            .map(|input| INPUT_LABEL.width() + input.text().width() + 1)
             .unwrap_or(0);
-        (2 + max_width as u16, 2 + self.mounts.len() as u16)
+        let max_width = [
+            self.title().width(),
+            NO_MOUNTS.width() + 3,
+            INPUT_HINT.width() + 3,
+            input_width,
+            row_width,
+        ]
+        .into_iter()
+        .max()
+        .unwrap_or(0);
+        // one more line for path input
+        (2 + max_width as u16, 3 + self.visible.len().max(1) as u16)
     }
 
-    fn try_finish(mut self: Box<Self>, app: &mut App) -> Result<(), Box<dyn Dialog>> {
-        if let Some(mount) = self.chosen {
-            app.start_scan(self.mounts.swap_remove(mount));
+    fn try_finish(self: Box<Self>, app: &mut App) -> Result<(), Box<dyn Dialog>> {
+        if let Some(path) = self.chosen {
+            app.start_scan(path);
             return Ok(());
         }
 
@@ -1,7 +1,7 @@
 use num_format::{CustomFormat, Grouping};
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
-use ratatui::style::{Color, Style};
+use ratatui::style::Style;
 use ratatui::widgets::{Block, BorderType, Borders, Clear, Widget};
 use unicode_width::UnicodeWidthStr;
 
@@ -59,11 +59,53 @@ impl ScanStatsDialog {
         }
         if let Some(total) = stats.total_size {
             lines.push(format!("Total size: {}", utils::byte_to_str(total, 0)));
+            lines.push("Differs from used space? Press 'G'".into());
         }
 
         lines.push(files);
         lines.push(dirs);
         lines.push(format!("Scan took: {:?}", stats.scan_duration));
+        if stats.watcher_events > 0 {
+            let mut events = num_format::Buffer::new();
+            events.write_formatted(&(stats.watcher_events), &format);
+            let mut rescans = num_format::Buffer::new();
+            rescans.write_formatted(&(stats.watcher_rescans), &format);
+            lines.push(format!(
+                "Rescans by watcher: {} (events: {})",
+                rescans.as_str(),
+                events.as_str()
+            ));
+        }
+        if stats.scan_errors > 0 {
+            let mut errors = num_format::Buffer::new();
+            errors.write_formatted(&(stats.scan_errors), &format);
+            lines.push(format!("Unreadable dirs: {} (press 'E')", errors.as_str()));
+        }
+        if stats.incomplete_dirs > 0 {
+            let mut incomplete = num_format::Buffer::new();
+            incomplete.write_formatted(&(stats.incomplete_dirs), &format);
+            lines.push(format!(
+                "Not scanned dirs: {} (scan cancelled)",
+                incomplete.as_str()
+            ));
+        }
+        if stats.unwatched_dirs > 0 {
+            let unwatched = {
+                let mut buf = num_format::Buffer::new();
+                buf.write_formatted(&(stats.unwatched_dirs), &format);
+                format!("Unwatched dirs: {}", buf.as_str())
+            };
+            lines.push(unwatched);
+            if let Some(limit) = stats.watch_limit {
+                // each directory (including root) requires its own watch
+                lines.push(format!(
+                    "Watch limit: {} (required: {})",
+                    limit,
+                    stats.dirs + 1
+                ));
+                lines.push("Increase fs.inotify.max_user_watches".into());
+            }
+        }
         if let Some(memory) = stats.used_memory {
             lines.push(format!("Memory usage: {}", utils::byte_to_str(memory, 0)));
         }
@@ -83,17 +125,17 @@ impl InputHandler for ScanStatsDialog {
 }
 
 impl Dialog for ScanStatsDialog {
-    fn get_widget<'a>(&'a self, app: &'a App) -> DialogWidget<'_> {
+    fn get_widget<'a>(&'a self, app: &'a App) -> DialogWidget<'a> {
         DialogWidget(self, app)
     }
 
     fn render(&self, app: &App, area: Rect, buf: &mut Buffer) {
         Clear.render(area, buf);
-        buf.set_style(area, Style::default().bg(Color::Black));
+        buf.set_style(area, Style::default().bg(app.theme.background));
 
         let block = Block::default()
             .borders(Borders::ALL)
-            .style(Style::default().fg(Color::White))
+            .style(Style::default().fg(app.theme.text))
             .title(Self::TITLE)
             .border_type(BorderType::Plain);
         Widget::render(block, area, buf);
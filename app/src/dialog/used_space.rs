@@ -0,0 +1,212 @@
+use byte_unit::Byte;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Style;
+use ratatui::widgets::{Block, BorderType, Borders, Clear, Widget};
+use unicode_width::UnicodeWidthStr;
+
+use diskscan::ScanStats;
+
+use crate::app::App;
+use crate::dialog::{Dialog, DialogWidget};
+use crate::term::InputHandler;
+use crate::theme::Theme;
+use crate::utils;
+
+/// Explains difference between scanned size and space used on filesystem
+pub struct UsedSpaceDialog {
+    should_close: bool,
+}
+
+impl UsedSpaceDialog {
+    const TITLE: &'static str = "Used Space ";
+
+    pub fn new() -> Self {
+        Self {
+            should_close: false,
+        }
+    }
+
+    fn files_lines(app: &App) -> Vec<(String, Style)> {
+        let files = app.files.as_ref().unwrap();
+        // deleted open files are found only on linux
+        let deleted = cfg!(target_os = "linux").then(|| files.get_deleted_size());
+        UsedSpaceDialog::lines(
+            &files.stats,
+            deleted,
+            files.scanner.is_scanning(),
+            &app.theme,
+        )
+    }
+
+    /// Returns lines of explanation
+    ///
+    /// Size of deleted files is `None` if they can't be found on this platform
+    fn lines(
+        stats: &ScanStats,
+        deleted: Option<Byte>,
+        is_scanning: bool,
+        theme: &Theme,
+    ) -> Vec<(String, Style)> {
+        let text = Style::default();
+        let note = Style::default().fg(theme.muted);
+        let (total, available, free) =
+            match (stats.total_size, stats.available_size, stats.free_size) {
+                (Some(total), Some(available), Some(free)) => (
+                    total.get_bytes() as i128,
+                    available.get_bytes() as i128,
+                    free.get_bytes() as i128,
+                ),
+                _ => return vec![("Filesystem stats are not available".into(), text)],
+            };
+        let scanned = stats.used_size.get_bytes() as i128;
+        let crossed = stats.crossed_size.get_bytes() as i128;
+        let used = total - free;
+        let reserved = free - available;
+        // data of other filesystems is not part of used space of this one
+        let difference = used - (scanned - crossed);
+        // the same as gray part of progress bar
+        let unknown = total - available - scanned;
+        let deleted_size = deleted.map(|d| d.get_bytes() as i128).unwrap_or(0);
+
+        let mut lines = vec![
+            (format!("Scanned: {}", size_to_str(scanned)), text),
+            (format!("Used on filesystem: {}", size_to_str(used)), text),
+            (format!("Not explained: {}", size_to_str(unknown)), text),
+            ("  gray part of bar, consists of:".into(), note),
+            (String::new(), text),
+            (format!("Reserved blocks: {}", size_to_str(reserved)), text),
+            (
+                "  free space that only root can use, not counted as used".into(),
+                note,
+            ),
+        ];
+        if crossed > 0 {
+            lines.push((
+                format!("Other filesystems: {}", size_to_str(-crossed)),
+                text,
+            ));
+            lines.push(("  scanned mounts, not part of used space".into(), note));
+        }
+        if stats.skipped_mounts > 0 {
+            lines.push((format!("Skipped mounts: {}", stats.skipped_mounts), text));
+            lines.push((
+                "  files hidden under mount points can't be measured".into(),
+                note,
+            ));
+        }
+        if stats.skipped_snapshots > 0 {
+            lines.push((
+                format!("Skipped snapshots: {}", stats.skipped_snapshots),
+                text,
+            ));
+            lines.push(("  data shared with subvolumes is counted once".into(), note));
+        }
+        if stats.scan_errors > 0 {
+            lines.push((format!("Unreadable dirs: {}", stats.scan_errors), text));
+            lines.push(("  their size is unknown (press 'E' for list)".into(), note));
+        }
+        if stats.incomplete_dirs > 0 {
+            lines.push((format!("Not scanned dirs: {}", stats.incomplete_dirs), text));
+            lines.push(("  scan was cancelled before they were read".into(), note));
+        }
+        match deleted {
+            Some(_) => lines.push((
+                format!("Deleted but open files: {}", size_to_str(deleted_size)),
+                text,
+            )),
+            None => lines.push(("Deleted but open files: not measured".into(), text)),
+        }
+        lines.push((
+            "  space is freed only after process closes them (press 'U')".into(),
+            note,
+        ));
+        lines.push((
+            format!(
+                "Metadata and other: {}",
+                size_to_str(difference - deleted_size)
+            ),
+            text,
+        ));
+        if stats.is_mount_point {
+            lines.push((
+                "  directories, journal and other filesystem structures".into(),
+                note,
+            ));
+        } else {
+            lines.push((
+                "  includes files outside of scanned path (it is not a mount point)".into(),
+                note,
+            ));
+        }
+        if is_scanning {
+            lines.push((String::new(), text));
+            lines.push((
+                "Scan is in progress, difference will decrease".into(),
+                Style::default().fg(theme.accent),
+            ));
+        }
+
+        lines
+    }
+}
+
+impl InputHandler for UsedSpaceDialog {
+    fn on_esc(&mut self) {
+        self.should_close = true;
+    }
+
+    fn on_key(&mut self, c: char) {
+        self.should_close = c == 'q' || c == 'g';
+    }
+}
+
+impl Dialog for UsedSpaceDialog {
+    fn get_widget<'a>(&'a self, app: &'a App) -> DialogWidget<'a> {
+        DialogWidget(self, app)
+    }
+
+    fn render(&self, app: &App, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+        buf.set_style(area, Style::default().bg(app.theme.background));
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .style(Style::default().fg(app.theme.text))
+            .title(Self::TITLE)
+            .border_type(BorderType::Plain);
+        Widget::render(block, area, buf);
+
+        let lines = UsedSpaceDialog::files_lines(app);
+        for (i, (line, style)) in lines.iter().enumerate() {
+            buf.set_string(area.x + 2, area.y + 1 + i as u16, line, *style);
+        }
+    }
+
+    fn size(&self, app: &App) -> (u16, u16) {
+        let lines = UsedSpaceDialog::files_lines(app);
+        let max_width = std::iter::once(Self::TITLE.width())
+            .chain(lines.iter().map(|(m, _)| m.width()))
+            .max()
+            .unwrap();
+        (4 + max_width as u16, 2 + lines.len() as u16)
+    }
+
+    fn try_finish(self: Box<Self>, _: &mut App) -> Result<(), Box<dyn Dialog>> {
+        if self.should_close {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+/// Converts signed size to string, negative sizes are prefixed with minus
+fn size_to_str(size: i128) -> String {
+    let bytes = Byte::from_bytes(size.unsigned_abs() as u64);
+    if size < 0 {
+        format!("-{}", utils::byte_to_str(bytes, 0))
+    } else {
+        utils::byte_to_str(bytes, 0)
+    }
+}
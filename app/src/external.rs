@@ -0,0 +1,98 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+
+use diskscan::EntryPath;
+
+/// Program that can be started at selected entry
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Program {
+    /// `$SHELL` inside directory
+    Shell,
+    /// Default application of system (`xdg-open`)
+    Opener,
+    /// `$PAGER` with file
+    Pager,
+    /// `$VISUAL` or `$EDITOR` with file
+    Editor,
+}
+
+/// Program that should be started while terminal is suspended
+#[derive(Clone, Debug)]
+pub struct ExternalCommand {
+    pub program: Program,
+    /// Entry that is opened by program
+    pub path: PathBuf,
+    /// Directory that is rescanned after program exits
+    pub rescan: EntryPath,
+}
+
+impl ExternalCommand {
+    /// Runs program and waits until it exits
+    pub fn run(&self) -> Result<()> {
+        let mut command = match self.program {
+            Program::Shell => {
+                let mut command = Command::new(shell());
+                command.current_dir(&self.path);
+                command
+            }
+            Program::Opener => opener(),
+            Program::Pager => from_env(&["PAGER"], "less")?,
+            Program::Editor => from_env(&["VISUAL", "EDITOR"], "vi")?,
+        };
+        if self.program != Program::Shell {
+            command.arg(&self.path);
+        }
+        let name = command.get_program().to_string_lossy().into_owned();
+        let status = command
+            .status()
+            .with_context(|| format!("failed to start '{}'", name))?;
+        if !status.success() {
+            bail!("'{}' exited with {}", name, status);
+        }
+        Ok(())
+    }
+}
+
+/// Creates command from environment variable that can contain arguments (e.g. `less -R`)
+fn from_env(vars: &[&str], default: &str) -> Result<Command> {
+    let value = vars
+        .iter()
+        .filter_map(std::env::var_os)
+        .find(|v| !v.is_empty())
+        .map(|v| v.to_string_lossy().into_owned())
+        .unwrap_or_else(|| default.to_string());
+    let mut parts = value.split_whitespace();
+    let Some(program) = parts.next() else {
+        bail!("program is not set in {}", vars.join(" or "));
+    };
+    let mut command = Command::new(program);
+    command.args(parts);
+    Ok(command)
+}
+
+fn opener() -> Command {
+    if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        // empty title, otherwise quoted path is treated as title
+        command.args(["/C", "start", ""]);
+        command
+    } else if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else {
+        Command::new("xdg-open")
+    }
+}
+
+fn shell() -> String {
+    let (var, default) = if cfg!(windows) {
+        ("COMSPEC", "cmd.exe")
+    } else {
+        ("SHELL", "/bin/sh")
+    };
+    std::env::var(var)
+        .ok()
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| default.to_string())
+}
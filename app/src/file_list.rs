@@ -1,12 +1,15 @@
 use std::cmp;
+use std::str::FromStr;
 
+use anyhow::{bail, Result};
 use byte_unit::Byte;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
-use ratatui::style::{Color, Style};
+use ratatui::style::{Modifier, Style};
 use ratatui::widgets::{Block, StatefulWidget, Widget};
 use unicode_width::UnicodeWidthStr;
 
+use crate::theme::Theme;
 use crate::utils;
 
 #[derive(Debug, Clone, Default)]
@@ -15,9 +18,21 @@ pub struct FileListState {
     selected: usize,
     busy_item: Option<usize>,
     spinner_state: usize,
+    /// Area of items and their count during last render, used to find clicked item
+    area: Rect,
+    items_count: usize,
 }
 
 impl FileListState {
+    /// Returns index of item that is drawn at given cell
+    pub fn item_at(&self, x: u16, y: u16) -> Option<usize> {
+        if !utils::contains(self.area, x, y) {
+            return None;
+        }
+        let index = self.offset + (y - self.area.y) as usize;
+        (index < self.items_count).then_some(index)
+    }
+
     pub fn selected(&self) -> usize {
         self.selected
     }
@@ -34,35 +49,97 @@ impl FileListState {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FileListItem {
     name: String,
+    /// Drawn before name, e.g. indentation guides of tree
+    prefix: String,
     size: Byte,
     style: Style,
+    unwatched: bool,
+    incomplete: bool,
+    error: bool,
 }
 
 impl FileListItem {
     pub fn new(name: String, size: Byte) -> FileListItem {
         FileListItem {
             name,
+            prefix: String::new(),
             size,
             style: Style::default(),
+            unwatched: false,
+            incomplete: false,
+            error: false,
         }
     }
 
+    pub fn error(mut self, error: bool) -> FileListItem {
+        self.error = error;
+        self
+    }
+
+    pub fn incomplete(mut self, incomplete: bool) -> FileListItem {
+        self.incomplete = incomplete;
+        self
+    }
+
+    pub fn prefix(mut self, prefix: String) -> FileListItem {
+        self.prefix = prefix;
+        self
+    }
+
     pub fn style(mut self, style: Style) -> FileListItem {
         self.style = style;
         self
     }
+
+    pub fn unwatched(mut self, unwatched: bool) -> FileListItem {
+        self.unwatched = unwatched;
+        self
+    }
+}
+
+/// Column that is shown after name of file
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Column {
+    /// Bar with length proportional to size
+    Bar,
+    Percent,
+    Size,
+}
+
+impl Column {
+    pub const DEFAULT: [Column; 2] = [Column::Bar, Column::Size];
+}
+
+impl FromStr for Column {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "bar" => Ok(Column::Bar),
+            "percent" => Ok(Column::Percent),
+            "size" => Ok(Column::Size),
+            _ => bail!("unknown column '{}', available: bar, percent, size", s),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct FileList<'a> {
     block: Option<Block<'a>>,
     items: Vec<FileListItem>,
+    columns: Vec<Column>,
     highlight_style: Style,
     simple_graphics: bool,
+    theme: Theme,
+    total_size: Option<Byte>,
 }
 
 const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
 const SPINNER_SIMPLE: [char; 4] = ['/', '-', '\\', '|'];
+const UNWATCHED_SYMBOL: char = '!';
+const INCOMPLETE_SYMBOL: char = '?';
+const ERROR_SYMBOL: char = '✗';
+const ERROR_SYMBOL_SIMPLE: char = 'x';
 
 impl<'a> FileList<'a> {
     pub fn new<T>(items: T) -> FileList<'a>
@@ -72,8 +149,11 @@ impl<'a> FileList<'a> {
         FileList {
             block: None,
             items: items.into(),
+            columns: Column::DEFAULT.to_vec(),
             highlight_style: Style::default(),
             simple_graphics: false,
+            theme: Theme::default(),
+            total_size: None,
         }
     }
 
@@ -82,6 +162,11 @@ impl<'a> FileList<'a> {
         self
     }
 
+    pub fn columns(mut self, columns: Vec<Column>) -> FileList<'a> {
+        self.columns = columns;
+        self
+    }
+
     pub fn highlight_style(mut self, style: Style) -> FileList<'a> {
         self.highlight_style = style;
         self
@@ -92,6 +177,17 @@ impl<'a> FileList<'a> {
         self
     }
 
+    pub fn theme(mut self, theme: Theme) -> FileList<'a> {
+        self.theme = theme;
+        self
+    }
+
+    /// Sets size that corresponds to full bar, by default it is sum of all items
+    pub fn total_size(mut self, total_size: Byte) -> FileList<'a> {
+        self.total_size = Some(total_size);
+        self
+    }
+
     fn get_items_bounds(
         &self,
         selected: usize,
@@ -136,6 +232,8 @@ impl<'a> StatefulWidget for FileList<'a> {
             None => area,
         };
 
+        state.area = list_area;
+        state.items_count = self.items.len();
         if list_area.width < 1 || list_area.height < 1 {
             return;
         }
@@ -157,9 +255,26 @@ impl<'a> StatefulWidget for FileList<'a> {
         let busy_symbol = spinner[state.spinner_state].to_string();
         let blank_symbol = " ".repeat(3);
         // space between elements
-        let spaces = 5;
+        let spaces = 4;
 
-        let total_size: u64 = self.items.iter().map(|f| f.size.get_bytes()).sum();
+        let total_size: u64 = match self.total_size {
+            Some(size) => size.get_bytes(),
+            None => self.items.iter().map(|f| f.size.get_bytes()).sum(),
+        };
+        // names are aligned, so column is widened by the longest prefix
+        let prefix_width = self
+            .items
+            .iter()
+            .map(|f| f.prefix.width())
+            .max()
+            .unwrap_or(0);
+        // sizes are aligned if other columns follow them
+        let size_column_width = self.items[start..end]
+            .iter()
+            .map(|f| utils::byte_to_str(f.size, 0).width())
+            .max()
+            .unwrap_or(0);
+        let last_column = self.columns.last().copied();
 
         for (i, item) in self
             .items
@@ -187,14 +302,27 @@ impl<'a> StatefulWidget for FileList<'a> {
             } else {
                 &blank_symbol
             };
-            let max_name_width = cmp::min(30, list_area.width);
+            let max_name_width = cmp::min(30 + prefix_width as u16, list_area.width);
             let (elem_x, max_name_width) = {
                 let (elem_x, _) =
                     buf.set_stringn(x, y, symbol, max_name_width as usize, item_style);
                 (elem_x, (max_name_width - (elem_x - x)))
             };
+            let (name_x, _) = buf.set_stringn(
+                elem_x,
+                y,
+                &item.prefix,
+                max_name_width as usize,
+                Style::default().fg(self.theme.dim),
+            );
             let line = &item.name;
-            buf.set_stringn(elem_x, y, line, max_name_width as usize, item.style);
+            buf.set_stringn(
+                name_x,
+                y,
+                line,
+                (max_name_width - (name_x - elem_x)) as usize,
+                item.style,
+            );
 
             if is_selected {
                 buf.set_style(area, self.highlight_style);
@@ -204,44 +332,108 @@ impl<'a> StatefulWidget for FileList<'a> {
                     elem_x + max_name_width + 1,
                     y,
                     busy_symbol.clone(),
-                    Style::default().fg(Color::LightYellow),
+                    Style::default().fg(self.theme.accent),
+                );
+            } else if item.error {
+                let symbol = if self.simple_graphics {
+                    ERROR_SYMBOL_SIMPLE
+                } else {
+                    ERROR_SYMBOL
+                };
+                buf.set_string(
+                    elem_x + max_name_width + 1,
+                    y,
+                    symbol.to_string(),
+                    Style::default().fg(self.theme.error),
+                );
+            } else if item.incomplete {
+                buf.set_string(
+                    elem_x + max_name_width + 1,
+                    y,
+                    INCOMPLETE_SYMBOL.to_string(),
+                    Style::default().fg(self.theme.muted),
+                );
+            } else if item.unwatched {
+                buf.set_string(
+                    elem_x + max_name_width + 1,
+                    y,
+                    UNWATCHED_SYMBOL.to_string(),
+                    Style::default().fg(self.theme.error),
                 );
             }
 
             let size_str = utils::byte_to_str(item.size, 0);
+            let percent = if total_size > 0 {
+                item.size.get_bytes() as f64 * 100.0 / total_size as f64
+            } else {
+                0.0
+            };
+            let percent_str = format!("{:>5.1}%", percent);
+            let column_text = |column: Column| match column {
+                Column::Bar => String::new(),
+                Column::Percent => percent_str.clone(),
+                Column::Size if last_column == Some(Column::Size) => size_str.clone(),
+                Column::Size => format!("{:>1$}", size_str, size_column_width),
+            };
+
+            // bar takes all space that is left after other columns (each followed by space)
+            let other_width: usize = self
+                .columns
+                .iter()
+                .filter(|&&c| c != Column::Bar)
+                .map(|&c| column_text(c).width() + 1)
+                .sum();
             let size_width = list_area.width.saturating_sub(
-                max_name_width + (size_str.width() + highlight_symbol.width()) as u16 + spaces,
+                max_name_width + (other_width + highlight_symbol.width()) as u16 + spaces,
             );
 
             let size = (item.size.get_bytes() as f64 * size_width as f64) / total_size as f64;
             let size_full = size as u64;
             let size_frac = size - size_full as f64;
 
-            if self.simple_graphics {
-                buf.set_string(
-                    elem_x + max_name_width + 3,
-                    y,
-                    " ".repeat(size_full as usize),
-                    Style::default().bg(Color::LightYellow),
-                );
-            } else {
-                let mut str = utils::get_unicode_block(1.0).repeat(size_full as usize);
-                str.push_str(utils::get_unicode_block(size_frac));
-
-                buf.set_string(
-                    elem_x + max_name_width + 3,
-                    y,
-                    str,
-                    Style::default().fg(Color::LightYellow),
-                );
+            let mut column_x = elem_x + max_name_width + 3;
+            for &column in &self.columns {
+                let remaining = list_area.right().saturating_sub(column_x) as usize;
+                match column {
+                    Column::Bar if self.simple_graphics => {
+                        buf.set_stringn(
+                            column_x,
+                            y,
+                            " ".repeat(size_full as usize),
+                            remaining,
+                            // reversed instead of background, so bar is visible without colors
+                            Style::default()
+                                .fg(self.theme.accent)
+                                .add_modifier(Modifier::REVERSED),
+                        );
+                        column_x += size_full as u16 + 2;
+                    }
+                    Column::Bar => {
+                        let mut str = utils::get_unicode_block(1.0).repeat(size_full as usize);
+                        str.push_str(utils::get_unicode_block(size_frac));
+
+                        buf.set_stringn(
+                            column_x,
+                            y,
+                            str,
+                            remaining,
+                            Style::default().fg(self.theme.accent),
+                        );
+                        column_x += size_full as u16 + 2;
+                    }
+                    column => {
+                        let text = column_text(column);
+                        buf.set_stringn(
+                            column_x,
+                            y,
+                            &text,
+                            remaining,
+                            Style::default().fg(self.theme.accent),
+                        );
+                        column_x += text.width() as u16 + 1;
+                    }
+                }
             }
-
-            buf.set_string(
-                elem_x + max_name_width + 5 + size_full as u16,
-                y,
-                &size_str,
-                Style::default().fg(Color::LightYellow),
-            );
         }
 
         state.spinner_state = (state.spinner_state + 1) % spinner.len();
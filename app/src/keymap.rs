@@ -0,0 +1,575 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Context, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+
+use diskscan::config::OneOrMany;
+
+/// Named action that can be bound to keys
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Action {
+    Back,
+    Cancel,
+    Copy,
+    Delete,
+    Deleted,
+    Down,
+    Editor,
+    End,
+    Errors,
+    Expand,
+    Files,
+    GoTo,
+    Help,
+    HistoryBack,
+    HistoryForward,
+    Home,
+    Left,
+    Log,
+    NewScan,
+    Open,
+    OpenExternal,
+    PageDown,
+    PageUp,
+    Pager,
+    Pause,
+    Quit,
+    Rescan,
+    Right,
+    ScanSelected,
+    Shell,
+    Stats,
+    Tree,
+    Treemap,
+    Up,
+    UsedSpace,
+}
+
+/// All actions with their names in keymap file, descriptions
+/// and default keys, in order they are shown in help
+const ACTIONS: &[(Action, &str, &str, &[&str])] = &[
+    (Action::Help, "help", "return to this screen", &["h", "F1"]),
+    (Action::NewScan, "new-scan", "start a new scan", &["n"]),
+    (
+        Action::ScanSelected,
+        "scan-selected",
+        "start a new scan at selected directory",
+        &["o"],
+    ),
+    (
+        Action::Rescan,
+        "rescan",
+        "rescan opened directory",
+        &["r", "F5"],
+    ),
+    (Action::Pause, "pause", "pause or resume scan", &["p"]),
+    (Action::Cancel, "cancel", "cancel scan", &["c"]),
+    (Action::Files, "files", "open files list", &["f"]),
+    (Action::Up, "up", "move up inside list", &["Up"]),
+    (Action::Down, "down", "move down inside list", &["Down"]),
+    (Action::PageUp, "page-up", "move page up", &["PageUp"]),
+    (
+        Action::PageDown,
+        "page-down",
+        "move page down",
+        &["PageDown"],
+    ),
+    (Action::Home, "home", "move to the first line", &["Home"]),
+    (Action::End, "end", "move to the last line", &["End"]),
+    (Action::Open, "open", "open selected directory", &["Enter"]),
+    (
+        Action::Right,
+        "right",
+        "open or expand directory",
+        &["Right"],
+    ),
+    (Action::Left, "left", "navigate up or collapse", &["Left"]),
+    (Action::Back, "back", "navigate up", &["Esc", "Backspace"]),
+    (
+        Action::HistoryBack,
+        "history-back",
+        "open previously opened directory",
+        &["[", "alt+Left"],
+    ),
+    (
+        Action::HistoryForward,
+        "history-forward",
+        "open next directory in history",
+        &["]", "alt+Right"],
+    ),
+    (Action::GoTo, "go-to", "go to path", &["/"]),
+    (Action::Delete, "delete", "delete files", &["d"]),
+    (
+        Action::Copy,
+        "copy",
+        "copy path of selected entry or clicked log entry to clipboard",
+        &["y"],
+    ),
+    (
+        Action::Shell,
+        "shell",
+        "open shell in selected directory",
+        &["b"],
+    ),
+    (
+        Action::OpenExternal,
+        "open-external",
+        "open selected entry in default app",
+        &["x"],
+    ),
+    (
+        Action::Pager,
+        "pager",
+        "view selected file in $PAGER",
+        &["w"],
+    ),
+    (
+        Action::Editor,
+        "editor",
+        "edit selected file in $EDITOR",
+        &["ctrl+e"],
+    ),
+    (
+        Action::Treemap,
+        "treemap",
+        "switch to treemap (arrows move in it)",
+        &["t"],
+    ),
+    (Action::Tree, "tree", "switch tree view", &["v"]),
+    (
+        Action::Expand,
+        "expand",
+        "expand or collapse in tree view",
+        &["Space"],
+    ),
+    (Action::Stats, "stats", "show scan statistics", &["s"]),
+    (Action::Errors, "errors", "list scan errors", &["e"]),
+    (
+        Action::Deleted,
+        "deleted",
+        "list deleted files that are still open",
+        &["u"],
+    ),
+    (
+        Action::UsedSpace,
+        "used-space",
+        "explain difference with used space",
+        &["g"],
+    ),
+    (Action::Log, "log", "open log", &["l"]),
+    (Action::Quit, "quit", "quit", &["q"]),
+];
+
+/// Keys that vi preset binds differently from default keymap
+const VI_KEYS: &[(Action, &[&str])] = &[
+    (Action::Left, &["h", "Left"]),
+    (Action::Down, &["j", "Down"]),
+    (Action::Up, &["k", "Up"]),
+    (Action::Right, &["l", "Right"]),
+    (Action::Home, &["g", "Home"]),
+    (Action::End, &["G", "End"]),
+    (Action::PageDown, &["ctrl+d", "ctrl+f", "PageDown"]),
+    (Action::PageUp, &["ctrl+u", "ctrl+b", "PageUp"]),
+    (Action::Help, &["?", "F1"]),
+    (Action::UsedSpace, &["i"]),
+    (Action::Log, &["L"]),
+];
+
+/// Keys that ncdu preset binds differently from default keymap
+const NCDU_KEYS: &[(Action, &[&str])] = &[
+    (Action::Left, &["h", "<", "Left"]),
+    (Action::Down, &["j", "Down"]),
+    (Action::Up, &["k", "Up"]),
+    (Action::Right, &["l", "Right"]),
+    (Action::Help, &["?", "F1"]),
+    (Action::Stats, &["i"]),
+    (Action::Log, &["L"]),
+];
+
+/// Names of presets that can be used instead of keymap file
+pub const PRESETS: &[&str] = &["default", "vi", "ncdu"];
+
+impl Action {
+    pub fn description(self) -> &'static str {
+        ACTIONS.iter().find(|a| a.0 == self).unwrap().2
+    }
+
+    fn from_name(name: &str) -> Option<Action> {
+        ACTIONS.iter().find(|a| a.1 == name).map(|a| a.0)
+    }
+}
+
+/// Key with modifiers
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Key {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl Key {
+    pub fn from_char(c: char) -> Key {
+        Key {
+            code: KeyCode::Char(c),
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    fn from_event(event: KeyEvent) -> Key {
+        let modifiers = if let KeyCode::Char(_) = event.code {
+            // shift is already applied to character
+            event.modifiers - KeyModifiers::SHIFT
+        } else {
+            event.modifiers
+        };
+        Key {
+            code: event.code,
+            modifiers,
+        }
+    }
+}
+
+impl FromStr for Key {
+    type Err = anyhow::Error;
+
+    /// Parses keys like `q`, `G`, `ctrl+d`, `Enter` or `F5`
+    fn from_str(s: &str) -> Result<Self> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut name = s;
+        // plus itself is a valid key, so only prefixes are split
+        while let Some((modifier, rest)) = name.split_once('+').filter(|(_, r)| !r.is_empty()) {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => bail!("unknown modifier '{}' in key '{}'", modifier, s),
+            };
+            name = rest;
+        }
+
+        let mut chars = name.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match name.to_lowercase().as_str() {
+                "backspace" => KeyCode::Backspace,
+                "delete" => KeyCode::Delete,
+                "down" => KeyCode::Down,
+                "end" => KeyCode::End,
+                "enter" => KeyCode::Enter,
+                "esc" => KeyCode::Esc,
+                "home" => KeyCode::Home,
+                "insert" => KeyCode::Insert,
+                "left" => KeyCode::Left,
+                "pagedown" => KeyCode::PageDown,
+                "pageup" => KeyCode::PageUp,
+                "right" => KeyCode::Right,
+                "space" => KeyCode::Char(' '),
+                "tab" => KeyCode::Tab,
+                "up" => KeyCode::Up,
+                lower => match lower.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    Some(n) if (1..=12).contains(&n) => KeyCode::F(n),
+                    _ => bail!("unknown key '{}'", s),
+                },
+            },
+        };
+        if let KeyCode::Char(c) = code {
+            if modifiers.contains(KeyModifiers::SHIFT) {
+                // the same as in events, shift is applied to character
+                modifiers -= KeyModifiers::SHIFT;
+                return Ok(Key {
+                    code: KeyCode::Char(c.to_ascii_uppercase()),
+                    modifiers,
+                });
+            }
+        }
+        Ok(Key { code, modifiers })
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+/// Contents of keymap file
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct KeymapFile {
+    preset: Option<String>,
+    #[serde(default)]
+    /// Keys of each action, single key can be written without array
+    keys: BTreeMap<String, OneOrMany>,
+}
+
+/// Bindings of keys to actions
+#[derive(Clone, Debug)]
+pub struct Keymap {
+    bindings: Vec<(Key, Action)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let mut keymap = Keymap { bindings: vec![] };
+        for &(action, _, _, keys) in ACTIONS {
+            keymap.set_static_keys(action, keys);
+        }
+        keymap
+    }
+}
+
+impl Keymap {
+    pub fn get_action(&self, event: KeyEvent) -> Option<Action> {
+        let key = Key::from_event(event);
+        self.bindings
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, action)| *action)
+    }
+
+    /// Returns all actions with keys bound to them, in order they should be shown in help
+    pub fn get_bindings(&self) -> Vec<(Action, Vec<Key>)> {
+        ACTIONS
+            .iter()
+            .map(|&(action, ..)| (action, self.get_keys(action)))
+            .filter(|(_, keys)| !keys.is_empty())
+            .collect()
+    }
+
+    pub fn get_keys(&self, action: Action) -> Vec<Key> {
+        self.bindings
+            .iter()
+            .filter(|(_, a)| *a == action)
+            .map(|(key, _)| *key)
+            .collect()
+    }
+
+    /// Loads keymap with given preset name or from file at given path
+    pub fn load(name: &str) -> Result<Keymap> {
+        if let Some(keymap) = Keymap::preset(name) {
+            return Ok(keymap);
+        }
+        let path = Path::new(name);
+        let text = std::fs::read_to_string(path).with_context(|| {
+            format!(
+                "failed to read keymap '{}' (available presets: {})",
+                path.display(),
+                PRESETS.join(", ")
+            )
+        })?;
+        Keymap::parse(&text).with_context(|| format!("invalid keymap '{}'", path.display()))
+    }
+
+    /// Parses keymap file
+    ///
+    /// Keys listed for action replace keys of this action in preset,
+    /// and are removed from other actions. The same key can't be listed for two actions
+    fn parse(text: &str) -> Result<Keymap> {
+        let file: KeymapFile = toml::from_str(text)?;
+        let preset = file.preset.as_deref().unwrap_or("default");
+        let mut keymap = Keymap::preset(preset).ok_or_else(|| {
+            anyhow!(
+                "unknown preset '{}', available: {}",
+                preset,
+                PRESETS.join(", ")
+            )
+        })?;
+        let mut bound: Vec<(Key, String)> = vec![];
+        for (name, keys) in file.keys {
+            let action =
+                Action::from_name(&name).ok_or_else(|| anyhow!("unknown action '{}'", name))?;
+            let keys = Vec::from(keys)
+                .iter()
+                .map(|k| k.parse())
+                .collect::<Result<Vec<Key>>>()
+                .with_context(|| format!("invalid keys of '{}'", name))?;
+            for key in &keys {
+                if let Some((_, other)) = bound.iter().find(|(k, _)| k == key) {
+                    bail!("key '{}' is bound to both '{}' and '{}'", key, other, name);
+                }
+                bound.push((*key, name.clone()));
+            }
+            keymap.set_keys(action, keys);
+        }
+        Ok(keymap)
+    }
+
+    pub fn preset(name: &str) -> Option<Keymap> {
+        let mut keymap = Keymap::default();
+        let keys = match name {
+            "default" => &[][..],
+            "vi" => VI_KEYS,
+            "ncdu" => NCDU_KEYS,
+            _ => return None,
+        };
+        for &(action, keys) in keys {
+            keymap.set_static_keys(action, keys);
+        }
+        Some(keymap)
+    }
+
+    fn set_keys(&mut self, action: Action, keys: Vec<Key>) {
+        self.bindings
+            .retain(|(key, a)| *a != action && !keys.contains(key));
+        self.bindings
+            .extend(keys.into_iter().map(|key| (key, action)));
+    }
+
+    /// Sets keys from builtin tables, they are always valid
+    fn set_static_keys(&mut self, action: Action, keys: &[&str]) {
+        let keys = keys.iter().map(|k| k.parse().unwrap()).collect();
+        self.set_keys(action, keys);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> Key {
+        Key { code, modifiers }
+    }
+
+    fn action(keymap: &Keymap, code: KeyCode, modifiers: KeyModifiers) -> Option<Action> {
+        keymap.get_action(KeyEvent::new(code, modifiers))
+    }
+
+    #[test]
+    fn parse_key() {
+        let none = KeyModifiers::NONE;
+        assert_eq!("q".parse::<Key>().unwrap(), key(KeyCode::Char('q'), none));
+        assert_eq!("G".parse::<Key>().unwrap(), key(KeyCode::Char('G'), none));
+        assert_eq!("+".parse::<Key>().unwrap(), key(KeyCode::Char('+'), none));
+        assert_eq!("Enter".parse::<Key>().unwrap(), key(KeyCode::Enter, none));
+        assert_eq!("pageup".parse::<Key>().unwrap(), key(KeyCode::PageUp, none));
+        assert_eq!(
+            "Space".parse::<Key>().unwrap(),
+            key(KeyCode::Char(' '), none)
+        );
+        assert_eq!("F12".parse::<Key>().unwrap(), key(KeyCode::F(12), none));
+        assert_eq!(
+            "ctrl+d".parse::<Key>().unwrap(),
+            key(KeyCode::Char('d'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            "Ctrl+Alt+Left".parse::<Key>().unwrap(),
+            key(KeyCode::Left, KeyModifiers::CONTROL | KeyModifiers::ALT)
+        );
+        assert_eq!(
+            "ctrl++".parse::<Key>().unwrap(),
+            key(KeyCode::Char('+'), KeyModifiers::CONTROL)
+        );
+        // shift is applied to character, the same as in key events
+        assert_eq!(
+            "shift+g".parse::<Key>().unwrap(),
+            key(KeyCode::Char('G'), none)
+        );
+        assert_eq!(
+            "shift+Tab".parse::<Key>().unwrap(),
+            key(KeyCode::Tab, KeyModifiers::SHIFT)
+        );
+    }
+
+    #[test]
+    fn parse_invalid_key() {
+        for s in ["", "F0", "F13", "enterr", "meta+q", "ctrl+"] {
+            assert!(s.parse::<Key>().is_err(), "'{}' is parsed", s);
+        }
+    }
+
+    #[test]
+    fn display_key() {
+        for s in ["q", "Ctrl+d", "Alt+Enter", "F5", "Space"] {
+            assert_eq!(s.parse::<Key>().unwrap().to_string(), s);
+        }
+    }
+
+    #[test]
+    fn presets() {
+        let none = KeyModifiers::NONE;
+        let default = Keymap::preset("default").unwrap();
+        assert_eq!(
+            action(&default, KeyCode::Char('q'), none),
+            Some(Action::Quit)
+        );
+        assert_eq!(action(&default, KeyCode::Char('j'), none), None);
+
+        let vi = Keymap::preset("vi").unwrap();
+        assert_eq!(action(&vi, KeyCode::Char('j'), none), Some(Action::Down));
+        assert_eq!(action(&vi, KeyCode::Down, none), Some(Action::Down));
+        // 'g' is taken from used space
+        assert_eq!(action(&vi, KeyCode::Char('g'), none), Some(Action::Home));
+        assert_eq!(
+            action(&vi, KeyCode::Char('i'), none),
+            Some(Action::UsedSpace)
+        );
+
+        assert!(Keymap::preset("emacs").is_none());
+    }
+
+    #[test]
+    fn parse_keymap() {
+        let none = KeyModifiers::NONE;
+        let keymap = Keymap::parse(
+            r#"
+            preset = "vi"
+
+            [keys]
+            quit = ["ctrl+q", "x"]
+            delete = "j"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            action(&keymap, KeyCode::Char('q'), KeyModifiers::CONTROL),
+            Some(Action::Quit)
+        );
+        // listed keys replace keys of action
+        assert_eq!(action(&keymap, KeyCode::Char('q'), none), None);
+        // and are removed from other actions of preset
+        assert_eq!(
+            action(&keymap, KeyCode::Char('j'), none),
+            Some(Action::Delete)
+        );
+        assert_eq!(
+            action(&keymap, KeyCode::Char('x'), none),
+            Some(Action::Quit)
+        );
+        assert_eq!(keymap.get_keys(Action::Down), ["Down".parse().unwrap()]);
+        // other keys of preset are kept
+        assert_eq!(action(&keymap, KeyCode::Char('k'), none), Some(Action::Up));
+
+        let keymap = Keymap::parse("[keys]\nlog = []").unwrap();
+        assert!(keymap.get_keys(Action::Log).is_empty());
+        assert!(!keymap.get_bindings().iter().any(|(a, _)| *a == Action::Log));
+    }
+
+    #[test]
+    fn parse_invalid_keymap() {
+        let error = |text| format!("{:#}", Keymap::parse(text).unwrap_err());
+        assert!(error("preset = \"emacs\"").contains("unknown preset 'emacs'"));
+        assert!(error("[keys]\njump = \"j\"").contains("unknown action 'jump'"));
+        assert!(error("[keys]\nquit = \"meta+q\"").contains("unknown modifier 'meta'"));
+        assert!(error("[keys]\nquit = 1").contains("string or array of strings"));
+        assert!(error("theme = \"dark\"").contains("unknown field `theme`"));
+        assert!(error("[keys]\nquit = \"x\"\ndelete = [\"d\", \"x\"]")
+            .contains("key 'x' is bound to both 'delete' and 'quit'"));
+    }
+}
@@ -7,15 +7,26 @@ use ratatui::widgets::{Block, StatefulWidget, Widget};
 use time::format_description::FormatItem;
 
 use crate::logger::LogEntry;
+use crate::theme::Theme;
+use crate::utils;
 
 #[derive(Debug, Clone, Default)]
 pub struct LogListState {
     offset: usize,
     follow: bool,
     move_pages: isize,
+    /// Entry that was clicked, it is highlighted
+    selected: Option<usize>,
+    /// Area of entries drawn during last render
+    area: Rect,
 }
 
 impl LogListState {
+    /// Returns index of entry that is drawn at given cell
+    pub fn item_at(&self, x: u16, y: u16) -> Option<usize> {
+        utils::contains(self.area, x, y).then(|| self.offset + (y - self.area.y) as usize)
+    }
+
     pub fn move_down(&mut self) {
         self.offset += 1;
     }
@@ -39,6 +50,14 @@ impl LogListState {
         self.follow = false;
     }
 
+    pub fn select(&mut self, index: Option<usize>) {
+        self.selected = index;
+    }
+
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
     pub fn set_follow(&mut self, follow: bool) {
         self.follow = follow;
     }
@@ -49,6 +68,7 @@ pub struct LogList<'a> {
     block: Option<Block<'a>>,
     entries: &'a [LogEntry],
     time_formatter: &'static [FormatItem<'static>],
+    theme: Theme,
 }
 
 impl<'a> LogList<'a> {
@@ -61,6 +81,7 @@ impl<'a> LogList<'a> {
             block: None,
             entries,
             time_formatter,
+            theme: Theme::default(),
         }
     }
 
@@ -98,14 +119,19 @@ impl<'a> LogList<'a> {
         (start, end)
     }
 
-    fn level_color(level: Level) -> Color {
+    fn level_color(&self, level: Level) -> Color {
         match level {
-            Level::Error => Color::Red,
-            Level::Warn => Color::Yellow,
-            Level::Info => Color::Green,
-            _ => Color::DarkGray,
+            Level::Error => self.theme.error,
+            Level::Warn => self.theme.accent,
+            Level::Info => self.theme.free,
+            _ => self.theme.dim,
         }
     }
+
+    pub fn theme(mut self, theme: Theme) -> LogList<'a> {
+        self.theme = theme;
+        self
+    }
 }
 
 impl<'a> StatefulWidget for LogList<'a> {
@@ -127,6 +153,7 @@ impl<'a> StatefulWidget for LogList<'a> {
         }
 
         if self.entries.is_empty() {
+            state.area = Rect::default();
             return;
         }
         let list_height = list_area.height as usize;
@@ -138,6 +165,10 @@ impl<'a> StatefulWidget for LogList<'a> {
         if end == self.entries.len() {
             state.follow = true;
         }
+        state.area = Rect {
+            height: (end - start) as u16,
+            ..list_area
+        };
 
         let scroll_height = std::cmp::max(1, (end - start) * list_height / self.entries.len());
         let mut scroll_offset = (list_height - scroll_height) * start
@@ -166,12 +197,12 @@ impl<'a> StatefulWidget for LogList<'a> {
                 .to_string();
 
             let mut spans = vec![
-                Span::styled(time, Style::default().fg(Color::DarkGray)),
+                Span::styled(time, Style::default().fg(self.theme.dim)),
                 Span::raw(" "),
                 Span::styled(
                     item.level.as_str(),
                     Style::default()
-                        .fg(LogList::level_color(item.level))
+                        .fg(self.level_color(item.level))
                         .add_modifier(Modifier::BOLD),
                 ),
                 Span::raw(" "),
@@ -186,6 +217,12 @@ impl<'a> StatefulWidget for LogList<'a> {
             ));
 
             buf.set_spans(x, y, &Spans::from(spans), list_area.width - 1);
+            if state.selected == Some(state.offset + i) {
+                buf.set_style(
+                    Rect::new(x, y, list_area.width - 1, 1),
+                    Style::default().add_modifier(Modifier::REVERSED),
+                );
+            }
 
             if self.entries.len() > list_height
                 && i >= scroll_offset
@@ -1,17 +1,32 @@
+use std::path::PathBuf;
 use std::time::Duration;
 
 use anyhow::Result;
 use clap::Parser;
 
+use diskscan::{ScannerBuilder, ScannerConfig, SortOrder};
+
+use crate::config::Config;
+use crate::file_list::Column;
+use crate::units::{SizeFormat, Units, MAX_PRECISION};
+
 mod app;
+mod config;
 mod dialog;
+mod external;
 mod file_list;
+mod keymap;
 mod log_list;
 mod logger;
 mod no_ui;
+mod path_input;
 mod progressbar;
+mod recent;
 mod term;
+mod theme;
+mod treemap;
 mod ui;
+mod units;
 mod utils;
 
 #[derive(Debug, Parser)]
@@ -25,21 +40,164 @@ pub struct Args {
     path: Option<String>,
 
     /// Use simple graphics instead of unicode
-    #[arg(short, long)]
-    simple_graphics: bool,
+    #[arg(short, long, num_args(0..=1), require_equals(true), default_missing_value("true"), value_name("BOOL"))]
+    simple_graphics: Option<bool>,
+
+    /// Refresh rate of terminal UI (in ms) [default: 200]
+    #[arg(short, long, value_parser(parse_duration), value_name("MS"))]
+    tick_rate: Option<Duration>,
+
+    /// Detect changes by checking directories with given interval (in ms)
+    /// instead of using file system notifications. Useful for network mounts.
+    /// Must be at least 100 ms
+    #[arg(long, value_parser(parse_poll_interval), value_name("MS"))]
+    poll_interval: Option<Duration>,
+
+    /// Stay on filesystem of scanned path. Other filesystems are detected
+    /// by device id, so bind mounts and new mounts are skipped too
+    #[arg(short = 'x', long, num_args(0..=1), require_equals(true), default_missing_value("true"), value_name("BOOL"))]
+    one_file_system: Option<bool>,
+
+    /// Also scan filesystem mounted at given path (implies --one-file-system).
+    /// Can be used multiple times
+    #[arg(long, value_name("PATH"))]
+    cross: Vec<String>,
+
+    /// Treat filesystem type as real so it can be scanned (e.g. fuse.sshfs).
+    /// Can be used multiple times
+    #[arg(long, value_name("TYPE"))]
+    real_fs: Vec<String>,
+
+    /// Treat filesystem type as pseudo so it is skipped during scan.
+    /// Can be used multiple times
+    #[arg(long, value_name("TYPE"))]
+    pseudo_fs: Vec<String>,
+
+    /// Skip contents of btrfs snapshots, so data shared with
+    /// their subvolumes is not counted several times
+    #[arg(long, num_args(0..=1), require_equals(true), default_missing_value("true"), value_name("BOOL"))]
+    skip_snapshots: Option<bool>,
+
+    /// Skip directories with given name (e.g. .git) or at given path.
+    /// Can be used multiple times
+    #[arg(long, value_name("NAME|PATH"))]
+    exclude: Vec<String>,
+
+    /// Key bindings: preset name (default, vi or ncdu) or path to keymap file [default: default]
+    #[arg(long, value_name("PRESET|FILE"))]
+    keymap: Option<String>,
+
+    /// Order of files: size or name [default: size]
+    #[arg(long, value_parser(config::parse_sort), value_name("ORDER"))]
+    sort: Option<SortOrder>,
+
+    /// Columns shown after file names: bar, percent and size [default: bar,size]
+    #[arg(long, value_delimiter(','), value_name("COLUMNS"))]
+    columns: Option<Vec<Column>>,
+
+    /// Units of sizes: binary (KiB), decimal (kB) or bytes (exact) [default: binary]
+    #[arg(long, value_name("UNITS"))]
+    units: Option<Units>,
+
+    /// Number of fractional digits in sizes [default: 2]
+    #[arg(long, value_parser(parse_precision), value_name("DIGITS"))]
+    precision: Option<usize>,
+
+    /// Color theme: dark, light or monochrome [default: dark, or monochrome if NO_COLOR is set]
+    #[arg(long, value_name("NAME"))]
+    theme: Option<String>,
+
+    /// Print path of selected entry on exit, e.g. `cd "$(spacedisplay --print-selected)"`
+    #[arg(long)]
+    print_selected: bool,
+
+    /// Path to config file [default: spacedisplay/config.toml in user config directory]
+    #[arg(long, value_name("FILE"))]
+    config: Option<PathBuf>,
+}
 
-    /// Refresh rate of terminal UI
-    #[arg(short, long, value_parser(parse_duration), default_value("200"))]
-    tick_rate: Duration,
+/// Options from command line and config file with defaults for options that are set in neither
+#[derive(Debug)]
+pub struct Options {
+    /// Colors that replace colors of theme, can be set only in config
+    pub colors: Vec<(String, Vec<String>)>,
+    pub columns: Vec<Column>,
+    pub keymap: String,
+    pub no_ui: bool,
+    pub path: Option<String>,
+    pub print_selected: bool,
+    pub scanner_builder: ScannerBuilder,
+    pub simple_graphics: bool,
+    pub size_format: SizeFormat,
+    pub sort: SortOrder,
+    /// Theme is `None` if it is not set, so default one is chosen by environment
+    pub theme: Option<String>,
+    pub tick_rate: Duration,
+}
+
+impl Options {
+    /// Merges options, command line takes precedence over config
+    ///
+    /// Lists of scanner (like `exclude`) from both sources are combined
+    pub fn new(args: Args, config: Config) -> Options {
+        let scanner_args = ScannerConfig {
+            cross: args.cross,
+            exclude: args.exclude,
+            one_file_system: args.one_file_system,
+            poll_interval: args.poll_interval,
+            pseudo_fs: args.pseudo_fs,
+            real_fs: args.real_fs,
+            rescan_delay: None,
+            skip_snapshots: args.skip_snapshots,
+        };
+        let default_format = SizeFormat::default();
+        Options {
+            colors: config.colors,
+            columns: args
+                .columns
+                .or(config.columns)
+                .unwrap_or_else(|| Column::DEFAULT.to_vec()),
+            keymap: args
+                .keymap
+                .or(config.keymap)
+                .unwrap_or_else(|| "default".to_string()),
+            no_ui: args.no_ui,
+            path: args.path,
+            print_selected: args.print_selected,
+            scanner_builder: ScannerBuilder::default()
+                .config(&config.scanner)
+                .config(&scanner_args),
+            simple_graphics: args
+                .simple_graphics
+                .or(config.simple_graphics)
+                .unwrap_or(false),
+            size_format: SizeFormat {
+                units: args.units.or(config.units).unwrap_or(default_format.units),
+                precision: args
+                    .precision
+                    .or(config.precision)
+                    .unwrap_or(default_format.precision),
+            },
+            sort: args.sort.or(config.sort).unwrap_or_default(),
+            theme: args.theme.or(config.theme),
+            tick_rate: args
+                .tick_rate
+                .or(config.tick_rate)
+                .unwrap_or(term::DEFAULT_TICK_RATE),
+        }
+    }
 }
 
 fn main() -> Result<()> {
     let args = Args::parse();
+    let config = Config::load(args.config.as_deref())?;
+    let options = Options::new(args, config);
+    SizeFormat::set_global(options.size_format);
 
-    if args.no_ui {
-        no_ui::run(args)?;
+    if options.no_ui {
+        no_ui::run(options)?;
     } else {
-        term::run(args)?;
+        term::run(options)?;
     }
 
     Ok(())
@@ -49,3 +207,52 @@ fn parse_duration(arg: &str) -> Result<Duration, std::num::ParseIntError> {
     let seconds = arg.parse()?;
     Ok(Duration::from_millis(seconds))
 }
+
+fn parse_poll_interval(arg: &str) -> Result<Duration> {
+    let interval = parse_duration(arg)?;
+    diskscan::config::check_poll_interval(interval).map_err(anyhow::Error::msg)?;
+    Ok(interval)
+}
+
+fn parse_precision(arg: &str) -> Result<usize> {
+    match arg.parse()? {
+        precision if precision <= MAX_PRECISION => Ok(precision),
+        _ => anyhow::bail!("must be between 0 and {}", MAX_PRECISION),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use crate::config::Config;
+    use crate::{Args, Options};
+
+    fn parse_options(args: &[&str], config: &str) -> Options {
+        let args = Args::parse_from([&["spacedisplay"], args].concat());
+        Options::new(args, Config::parse(config).unwrap())
+    }
+
+    #[test]
+    fn command_line_overrides_config() {
+        let config = "simple_graphics = true\ntheme = \"light\"\nprecision = 3";
+        let options = parse_options(&["--simple-graphics=false", "--theme", "dark"], config);
+        assert!(!options.simple_graphics);
+        assert_eq!(options.theme.as_deref(), Some("dark"));
+        assert_eq!(options.size_format.precision, 3);
+
+        // flag without value enables option
+        let options = parse_options(&["-s"], "simple_graphics = false");
+        assert!(options.simple_graphics);
+    }
+
+    #[test]
+    fn scanner_options() {
+        let config = "[scanner]\none_file_system = true\nexclude = \".git\"\nrescan_delay = 500";
+        let options = parse_options(&["-x=false", "--exclude", "/tmp"], config);
+        let expected = diskscan::ScannerBuilder::default()
+            .excludes(vec![".git".to_string(), "/tmp".to_string()])
+            .rescan_delay(std::time::Duration::from_millis(500));
+        assert_eq!(options.scanner_builder, expected);
+    }
+}
@@ -5,13 +5,13 @@ use std::time::{Duration, Instant};
 use anyhow::Result;
 use crossterm::{cursor, terminal, ExecutableCommand, QueueableCommand};
 
-use diskscan::{ScanStats, ScannerBuilder, SnapshotConfig};
+use diskscan::{ScanStats, SnapshotConfig};
 
-use crate::{utils, Args};
+use crate::{utils, Options};
 
-pub fn run(args: Args) -> Result<()> {
-    if let Some(path) = args.path {
-        let scanner = ScannerBuilder::default().scan(path);
+pub fn run(options: Options) -> Result<()> {
+    if let Some(path) = options.path {
+        let scanner = options.scanner_builder.scan(path);
         let start = Instant::now();
         while scanner.is_scanning() {
             print_stats(scanner.stats())?;
@@ -29,6 +29,7 @@ pub fn run(args: Args) -> Result<()> {
                 scanner.get_scan_path(),
                 SnapshotConfig {
                     max_depth: 1,
+                    sort: options.sort,
                     ..SnapshotConfig::default()
                 },
             )
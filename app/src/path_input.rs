@@ -0,0 +1,220 @@
+use std::path::{Path, MAIN_SEPARATOR};
+
+/// Text input for file system path with tab completion and history
+#[derive(Debug, Default)]
+pub struct PathInput {
+    text: String,
+    /// Candidates of last completion and index of the one that was used
+    completions: Option<(Vec<String>, usize)>,
+    /// Previously entered paths, most recent last
+    history: Vec<String>,
+    /// Position in history while it is browsed
+    history_pos: Option<usize>,
+}
+
+impl PathInput {
+    pub fn new(history: Vec<String>) -> Self {
+        Self {
+            history,
+            ..Self::default()
+        }
+    }
+
+    pub fn backspace(&mut self) {
+        self.text.pop();
+        self.completions = None;
+    }
+
+    /// Completes last component of path to one of directories with such prefix
+    ///
+    /// If several directories match, path is completed to their common prefix.
+    /// Repeated calls cycle through all matching directories
+    pub fn complete(&mut self) {
+        if let Some((candidates, index)) = self.completions.as_mut() {
+            *index = (*index + 1) % candidates.len();
+            self.text = candidates[*index].clone();
+            return;
+        }
+
+        let (dir, prefix) = match self.text.rfind(is_separator) {
+            Some(pos) => self.text.split_at(pos + 1),
+            None => ("", self.text.as_str()),
+        };
+        let entries = match std::fs::read_dir(if dir.is_empty() { "." } else { dir }) {
+            Ok(entries) => entries,
+            Err(_) => return,
+        };
+        let mut names: Vec<_> = entries
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().is_dir())
+            .filter_map(|e| e.file_name().to_str().map(|s| s.to_string()))
+            .filter(|name| name.starts_with(prefix))
+            .collect();
+        names.sort();
+
+        match names.len() {
+            0 => {}
+            1 => self.text = format!("{}{}{}", dir, names[0], MAIN_SEPARATOR),
+            _ => {
+                let common = common_prefix(&names);
+                if common.len() > prefix.len() {
+                    self.text = format!("{}{}", dir, common);
+                } else {
+                    let candidates: Vec<_> = names
+                        .iter()
+                        .map(|name| format!("{}{}{}", dir, name, MAIN_SEPARATOR))
+                        .collect();
+                    self.text = candidates[0].clone();
+                    self.completions = Some((candidates, 0));
+                }
+            }
+        }
+    }
+
+    pub fn history_next(&mut self) {
+        if let Some(pos) = self.history_pos {
+            if pos + 1 < self.history.len() {
+                self.set_history_pos(pos + 1);
+            } else {
+                self.history_pos = None;
+                self.set_text(String::new());
+            }
+        }
+    }
+
+    pub fn history_prev(&mut self) {
+        let pos = match self.history_pos {
+            Some(pos) => pos.saturating_sub(1),
+            None if !self.history.is_empty() => self.history.len() - 1,
+            None => return,
+        };
+        self.set_history_pos(pos);
+    }
+
+    pub fn insert(&mut self, c: char) {
+        self.text.push(c);
+        self.completions = None;
+    }
+
+    /// Returns entered path if it points to existing directory
+    pub fn path(&self) -> Option<String> {
+        let path = Path::new(&self.text);
+        path.is_dir().then(|| {
+            // trailing separator is not needed, but root should keep it
+            let trimmed = self.text.trim_end_matches(is_separator);
+            if trimmed.is_empty() || trimmed.ends_with(':') {
+                self.text.clone()
+            } else {
+                trimmed.to_string()
+            }
+        })
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    fn set_history_pos(&mut self, pos: usize) {
+        self.history_pos = Some(pos);
+        self.set_text(self.history[pos].clone());
+    }
+
+    pub fn set_text(&mut self, text: String) {
+        self.text = text;
+        self.completions = None;
+    }
+}
+
+fn common_prefix(names: &[String]) -> &str {
+    let first = &names[0];
+    let len = names[1..].iter().fold(first.len(), |len, name| {
+        first
+            .char_indices()
+            .zip(name.chars())
+            .take_while(|((i, a), b)| *i < len && a == b)
+            .map(|((i, a), _)| i + a.len_utf8())
+            .last()
+            .unwrap_or(0)
+    });
+    &first[..len]
+}
+
+fn is_separator(c: char) -> bool {
+    std::path::is_separator(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::MAIN_SEPARATOR;
+
+    use super::*;
+
+    #[test]
+    fn prefix() {
+        let names = |names: &[&str]| names.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(common_prefix(&names(&["alpha", "alps", "alp"])), "alp");
+        assert_eq!(common_prefix(&names(&["alpha"])), "alpha");
+        assert_eq!(common_prefix(&names(&["alpha", "beta"])), "");
+        // prefix is not split inside multibyte character
+        assert_eq!(common_prefix(&names(&["äb", "äc"])), "ä");
+        assert_eq!(common_prefix(&names(&["ä", "ö"])), "");
+    }
+
+    #[test]
+    fn complete() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        for name in ["alpha", "alps", "beta"] {
+            std::fs::create_dir_all(dir.join(name)).unwrap();
+        }
+        std::fs::write(dir.join("alfile"), "").unwrap();
+        let dir_text = format!("{}{}", dir.display(), MAIN_SEPARATOR);
+        let text = |name: &str| format!("{}{}", dir_text, name);
+
+        let mut input = PathInput::default();
+        input.set_text(text("b"));
+        input.complete();
+        assert_eq!(input.text(), format!("{}{}", text("beta"), MAIN_SEPARATOR));
+        assert_eq!(input.path(), Some(text("beta")));
+
+        // files are not completed, directories are completed to common prefix
+        input.set_text(text("al"));
+        input.complete();
+        assert_eq!(input.text(), text("alp"));
+        // then each tab cycles through matching directories
+        input.complete();
+        assert_eq!(input.text(), format!("{}{}", text("alpha"), MAIN_SEPARATOR));
+        input.complete();
+        assert_eq!(input.text(), format!("{}{}", text("alps"), MAIN_SEPARATOR));
+        input.complete();
+        assert_eq!(input.text(), format!("{}{}", text("alpha"), MAIN_SEPARATOR));
+
+        // editing text starts new completion
+        input.backspace();
+        input.backspace();
+        input.complete();
+        assert_eq!(input.text(), format!("{}{}", text("alpha"), MAIN_SEPARATOR));
+
+        input.set_text(text("x"));
+        input.complete();
+        assert_eq!(input.text(), text("x"));
+        assert_eq!(input.path(), None);
+    }
+
+    #[test]
+    fn history() {
+        let mut input = PathInput::new(vec!["/first".to_string(), "/second".to_string()]);
+        input.history_next();
+        assert_eq!(input.text(), "");
+        input.history_prev();
+        assert_eq!(input.text(), "/second");
+        input.history_prev();
+        input.history_prev();
+        assert_eq!(input.text(), "/first");
+        input.history_next();
+        assert_eq!(input.text(), "/second");
+        // going past the most recent entry clears input
+        input.history_next();
+        assert_eq!(input.text(), "");
+    }
+}
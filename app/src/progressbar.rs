@@ -129,7 +129,7 @@ fn make_layout(items: &[BarItem], width: usize) -> Vec<(BarItem, usize)> {
         // for overdraw
         let items: Vec<_> = items
             .into_iter()
-            .zip(widths.into_iter())
+            .zip(widths)
             .map(|(item, mut width)| {
                 let available = width - item.label.width() as f64;
                 if available > 0.0 {
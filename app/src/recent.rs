@@ -0,0 +1,112 @@
+use std::path::{Path, PathBuf};
+
+/// Number of previous scan paths that are remembered
+const MAX_RECENT_ROOTS: usize = 20;
+
+/// Paths of previous scans that are kept between runs
+#[derive(Debug, Default)]
+pub struct RecentRoots {
+    /// Most recent path is last
+    paths: Vec<String>,
+    /// File that paths are stored in, `None` if they are kept only in memory
+    file: Option<PathBuf>,
+}
+
+impl RecentRoots {
+    /// Returns path to file inside user state directory
+    ///
+    /// It is `$XDG_STATE_HOME/spacedisplay/recent_roots` (or `~/.local/state/...`),
+    /// and `%LOCALAPPDATA%\spacedisplay\recent_roots` on Windows
+    pub fn default_path() -> Option<PathBuf> {
+        let dir = if cfg!(windows) {
+            std::env::var_os("LOCALAPPDATA").map(PathBuf::from)
+        } else {
+            std::env::var_os("XDG_STATE_HOME")
+                .filter(|dir| !dir.is_empty())
+                .map(PathBuf::from)
+                .or_else(|| {
+                    std::env::var_os("HOME").map(|home| Path::new(&home).join(".local/state"))
+                })
+        }?;
+        Some(dir.join("spacedisplay").join("recent_roots"))
+    }
+
+    /// Loads paths from given file, one path per line
+    ///
+    /// Missing or unreadable file gives empty list, paths are still saved to it later
+    pub fn load(file: PathBuf) -> Self {
+        let paths = std::fs::read_to_string(&file)
+            .map(|text| text.lines().map(|line| line.to_string()).collect())
+            .unwrap_or_default();
+        Self {
+            paths,
+            file: Some(file),
+        }
+    }
+
+    pub fn paths(&self) -> &[String] {
+        &self.paths
+    }
+
+    /// Moves path to the end of list and saves list to file
+    pub fn push(&mut self, path: String) {
+        self.paths.retain(|p| p != &path);
+        // path with line break can't be stored in file, so it is not remembered at all
+        if !path.contains('\n') {
+            self.paths.push(path);
+        }
+        if self.paths.len() > MAX_RECENT_ROOTS {
+            self.paths.drain(..self.paths.len() - MAX_RECENT_ROOTS);
+        }
+        if let Err(e) = self.save() {
+            log::warn!("Failed to save recent paths: {}", e);
+        }
+    }
+
+    fn save(&self) -> std::io::Result<()> {
+        let file = match &self.file {
+            Some(file) => file,
+            None => return Ok(()),
+        };
+        if let Some(dir) = file.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let text: String = self.paths.iter().map(|p| format!("{}\n", p)).collect();
+        std::fs::write(file, text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_moves_to_end() {
+        let mut recent = RecentRoots::default();
+        for i in 0..MAX_RECENT_ROOTS + 5 {
+            recent.push(format!("/dir{}", i));
+        }
+        assert_eq!(recent.paths().len(), MAX_RECENT_ROOTS);
+        assert_eq!(recent.paths()[0], "/dir5");
+
+        recent.push("/dir10".to_string());
+        assert_eq!(recent.paths().len(), MAX_RECENT_ROOTS);
+        assert_eq!(recent.paths().last().unwrap(), "/dir10");
+        assert_eq!(recent.paths().iter().filter(|p| *p == "/dir10").count(), 1);
+    }
+
+    #[test]
+    fn saved_between_runs() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("state").join("recent_roots");
+
+        let mut recent = RecentRoots::load(file.clone());
+        assert!(recent.paths().is_empty());
+        recent.push("/home".to_string());
+        recent.push("/tmp".to_string());
+        recent.push("/home".to_string());
+
+        let recent = RecentRoots::load(file);
+        assert_eq!(recent.paths(), ["/tmp", "/home"]);
+    }
+}
@@ -1,8 +1,10 @@
+use std::io::{IsTerminal, Write};
 use std::time::{Duration, Instant};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use crossterm::event::{
     DisableMouseCapture, EnableMouseCapture, Event as CEvent, KeyCode, KeyEvent, KeyEventKind,
+    MouseEvent,
 };
 use crossterm::terminal::{
     disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
@@ -12,7 +14,12 @@ use ratatui::backend::{Backend, CrosstermBackend};
 use ratatui::Terminal;
 
 use crate::app::App;
-use crate::{ui, Args};
+use crate::keymap::Keymap;
+use crate::recent::RecentRoots;
+use crate::theme::Theme;
+use crate::{ui, utils, Options};
+
+pub const DEFAULT_TICK_RATE: Duration = Duration::from_millis(200);
 
 pub trait InputHandler {
     fn on_press(&mut self, event: KeyEvent) {
@@ -24,7 +31,8 @@ pub trait InputHandler {
             KeyCode::Right => self.on_right(),
             KeyCode::Enter => self.on_enter(),
             KeyCode::Esc => self.on_esc(),
-            KeyCode::Backspace => self.on_esc(),
+            KeyCode::Backspace => self.on_backspace(),
+            KeyCode::Tab => self.on_tab(),
             KeyCode::F(n) => self.on_fn(n),
             KeyCode::PageDown => self.on_page_down(),
             KeyCode::PageUp => self.on_page_up(),
@@ -33,7 +41,9 @@ pub trait InputHandler {
             _ => {}
         }
     }
-    fn on_backspace(&mut self) {}
+    fn on_backspace(&mut self) {
+        self.on_esc();
+    }
     fn on_down(&mut self) {}
     fn on_end(&mut self) {}
     fn on_enter(&mut self) {}
@@ -42,9 +52,11 @@ pub trait InputHandler {
     fn on_home(&mut self) {}
     fn on_key(&mut self, _c: char) {}
     fn on_left(&mut self) {}
+    fn on_mouse(&mut self, _event: MouseEvent) {}
     fn on_page_down(&mut self) {}
     fn on_page_up(&mut self) {}
     fn on_right(&mut self) {}
+    fn on_tab(&mut self) {}
     fn on_up(&mut self) {}
 }
 
@@ -69,17 +81,18 @@ impl<'a, B: Backend> AppRunner<'a, B> {
         }
     }
 
-    fn run(mut self, mut app: App) -> Result<()> {
+    /// Runs app until it quits or requests external program
+    fn run(mut self, app: &mut App) -> Result<()> {
         loop {
             self.terminal
-                .draw(|f| ui::draw(f, &mut app, self.simple_graphics))?;
+                .draw(|f| ui::draw(f, app, self.simple_graphics))?;
 
             app.check_input(&self);
             if self.last_tick.elapsed() >= self.tick_rate {
                 app.on_tick();
                 self.last_tick = Instant::now();
             }
-            if app.should_quit {
+            if app.should_quit || app.external.is_some() {
                 return Ok(());
             }
         }
@@ -93,10 +106,12 @@ impl<'a, B: Backend> InputProvider for AppRunner<'a, B> {
             .checked_sub(self.last_tick.elapsed())
             .unwrap_or_else(|| Duration::from_secs(0));
         if event::poll(timeout)? {
-            if let CEvent::Key(key_event) = event::read()? {
-                if key_event.kind == KeyEventKind::Press {
+            match event::read()? {
+                CEvent::Key(key_event) if key_event.kind == KeyEventKind::Press => {
                     handler.on_press(key_event);
                 }
+                CEvent::Mouse(mouse_event) => handler.on_mouse(mouse_event),
+                _ => {}
             }
         }
 
@@ -104,31 +119,73 @@ impl<'a, B: Backend> InputProvider for AppRunner<'a, B> {
     }
 }
 
-pub fn run(args: Args) -> Result<()> {
+pub fn run(options: Options) -> Result<()> {
+    // load keymap and theme before terminal is initialized so errors are printed normally
+    let keymap = Keymap::load(&options.keymap)?;
+    let theme = Theme::load(options.theme.as_deref(), &options.colors).context("invalid theme")?;
+
     let original_hook = std::panic::take_hook();
     std::panic::set_hook(Box::new(move |panic| {
         reset_terminal().unwrap();
         original_hook(panic);
     }));
 
-    let mut terminal = init_terminal()?;
-    let runner = AppRunner::new(&mut terminal, args.tick_rate, args.simple_graphics);
-    let mut app = App::new();
-    if let Some(path) = args.path {
+    let mut app = App::new(options.scanner_builder, keymap);
+    app.sort = options.sort;
+    app.columns = options.columns;
+    app.theme = theme;
+    if let Some(file) = RecentRoots::default_path() {
+        app.recent_roots = RecentRoots::load(file);
+    }
+    if let Some(path) = options.path {
         app.start_scan(path);
     }
-    let res = runner.run(app);
+    loop {
+        let mut terminal = init_terminal()?;
+        let res =
+            AppRunner::new(&mut terminal, options.tick_rate, options.simple_graphics).run(&mut app);
+        reset_terminal()?;
+        res?;
+
+        // terminal is suspended while external program is running
+        match app.external.take() {
+            Some(command) => {
+                let result = command.run();
+                app.on_external_exit(command, result);
+            }
+            None => break,
+        }
+    }
 
-    reset_terminal()?;
+    if options.print_selected {
+        if let Some(files) = &app.files {
+            let path = files
+                .get_selected_path()
+                .unwrap_or_else(|| files.current_path.clone());
+            println!("{}", path.get_path().display());
+        }
+    }
+    Ok(())
+}
 
-    res
+/// Copies text to clipboard of terminal with OSC 52 escape sequence
+///
+/// It works over SSH too, but terminal may ignore it (or ask for permission)
+pub fn copy_to_clipboard(text: &str) -> Result<()> {
+    // it is called only while input is handled, after `Terminal::draw` has written and
+    // flushed the whole frame, so sequence can't split it. It is flushed right away,
+    // so it is also not mixed with the next frame
+    let mut output = output();
+    write!(output, "\x1b]52;c;{}\x07", utils::base64(text.as_bytes()))?;
+    output.flush()?;
+    Ok(())
 }
 
-fn init_terminal() -> Result<Terminal<CrosstermBackend<std::io::Stdout>>> {
-    execute!(std::io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+fn init_terminal() -> Result<Terminal<CrosstermBackend<Box<dyn Write>>>> {
+    execute!(output(), EnterAlternateScreen, EnableMouseCapture)?;
     enable_raw_mode()?;
 
-    let backend = CrosstermBackend::new(std::io::stdout());
+    let backend = CrosstermBackend::new(output());
     let mut terminal = Terminal::new(backend)?;
     terminal.hide_cursor()?;
 
@@ -137,7 +194,18 @@ fn init_terminal() -> Result<Terminal<CrosstermBackend<std::io::Stdout>>> {
 
 fn reset_terminal() -> Result<()> {
     disable_raw_mode()?;
-    execute!(std::io::stdout(), LeaveAlternateScreen, DisableMouseCapture)?;
+    execute!(output(), LeaveAlternateScreen, DisableMouseCapture)?;
 
     Ok(())
 }
+
+/// Returns stream that UI is drawn to
+///
+/// If stdout is redirected (e.g. `cd "$(spacedisplay --print-selected)"`), stderr is used
+fn output() -> Box<dyn Write> {
+    if std::io::stdout().is_terminal() {
+        Box::new(std::io::stdout())
+    } else {
+        Box::new(std::io::stderr())
+    }
+}
@@ -0,0 +1,228 @@
+use anyhow::{anyhow, bail, Result};
+use ratatui::style::Color;
+
+/// Names of builtin themes
+pub const PRESETS: &[&str] = &["dark", "light", "monochrome"];
+
+/// Colors of UI elements
+#[derive(Clone, Debug, PartialEq)]
+pub struct Theme {
+    /// Main text and borders
+    pub text: Color,
+    /// Background of dialogs and selected tab
+    pub background: Color,
+    /// Title, directories, size bars and other highlighted values
+    pub accent: Color,
+    pub file: Color,
+    pub error: Color,
+    /// Mounted filesystems and processes
+    pub mount: Color,
+    pub subvolume: Color,
+    /// Notes and skipped entries
+    pub muted: Color,
+    /// Tree guides, timestamps and empty parts of bars
+    pub dim: Color,
+    pub tabs: Color,
+    /// Free space in text and progress bar
+    pub free: Color,
+    /// Progress bar part with size of opened directory
+    pub scanned: Color,
+    /// Progress bar part with size of scanned files outside of opened directory
+    pub hidden: Color,
+    /// Progress bar part with space that is used but not scanned
+    pub unknown: Color,
+    /// Labels of progress bar, label of unknown part uses background color
+    pub bar_text: Color,
+    /// Colors of directories in treemap, neighbours get different colors
+    pub palette: Vec<Color>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::dark()
+    }
+}
+
+impl Theme {
+    fn dark() -> Theme {
+        Theme {
+            text: Color::White,
+            background: Color::Black,
+            accent: Color::LightYellow,
+            file: Color::LightBlue,
+            error: Color::LightRed,
+            mount: Color::LightCyan,
+            subvolume: Color::LightMagenta,
+            muted: Color::Gray,
+            dim: Color::DarkGray,
+            tabs: Color::Cyan,
+            free: Color::Green,
+            scanned: Color::LightBlue,
+            hidden: Color::Blue,
+            unknown: Color::Gray,
+            bar_text: Color::White,
+            palette: vec![
+                Color::Blue,
+                Color::Green,
+                Color::Magenta,
+                Color::Cyan,
+                Color::Red,
+                Color::Yellow,
+            ],
+        }
+    }
+
+    /// Theme for light terminal backgrounds, uses 256 color palette
+    /// since basic colors depend on terminal settings too much
+    fn light() -> Theme {
+        Theme {
+            text: Color::Black,
+            background: Color::White,
+            accent: Color::Indexed(130),
+            file: Color::Indexed(25),
+            error: Color::Indexed(160),
+            mount: Color::Indexed(30),
+            subvolume: Color::Indexed(90),
+            muted: Color::Indexed(242),
+            dim: Color::Indexed(248),
+            tabs: Color::Indexed(31),
+            free: Color::Indexed(28),
+            scanned: Color::Indexed(32),
+            hidden: Color::Indexed(25),
+            unknown: Color::Indexed(244),
+            bar_text: Color::White,
+            palette: vec![
+                Color::Indexed(68),
+                Color::Indexed(71),
+                Color::Indexed(133),
+                Color::Indexed(37),
+                Color::Indexed(167),
+                Color::Indexed(179),
+            ],
+        }
+    }
+
+    /// Theme without colors, only default colors of terminal and modifiers are used
+    fn monochrome() -> Theme {
+        Theme {
+            text: Color::Reset,
+            background: Color::Reset,
+            accent: Color::Reset,
+            file: Color::Reset,
+            error: Color::Reset,
+            mount: Color::Reset,
+            subvolume: Color::Reset,
+            muted: Color::Reset,
+            dim: Color::Reset,
+            tabs: Color::Reset,
+            free: Color::Reset,
+            scanned: Color::Reset,
+            hidden: Color::Reset,
+            unknown: Color::Reset,
+            bar_text: Color::Reset,
+            palette: vec![Color::Reset],
+        }
+    }
+
+    /// Creates theme with given preset name and colors replaced by user
+    ///
+    /// If name is not set, `NO_COLOR` environment variable selects monochrome theme
+    pub fn load(name: Option<&str>, colors: &[(String, Vec<String>)]) -> Result<Theme> {
+        let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+        let name = name.unwrap_or(if no_color { "monochrome" } else { "dark" });
+        let mut theme = Theme::preset(name).ok_or_else(|| {
+            anyhow!(
+                "unknown theme '{}', available: {}",
+                name,
+                PRESETS.join(", ")
+            )
+        })?;
+        for (name, values) in colors {
+            theme.set_color(name, values)?;
+        }
+        Ok(theme)
+    }
+
+    pub fn preset(name: &str) -> Option<Theme> {
+        match name {
+            "dark" => Some(Theme::dark()),
+            "light" => Some(Theme::light()),
+            "monochrome" => Some(Theme::monochrome()),
+            _ => None,
+        }
+    }
+
+    /// Replaces color with given name, only palette can have several values
+    fn set_color(&mut self, name: &str, values: &[String]) -> Result<()> {
+        let colors = values
+            .iter()
+            .map(|v| parse_color(v))
+            .collect::<Result<Vec<_>>>()?;
+        if name == "palette" {
+            if colors.is_empty() {
+                bail!("palette must not be empty");
+            }
+            self.palette = colors;
+            return Ok(());
+        }
+        let color = match colors[..] {
+            [color] => color,
+            _ => bail!("'{}' must be a single color", name),
+        };
+        let field = match name {
+            "text" => &mut self.text,
+            "background" => &mut self.background,
+            "accent" => &mut self.accent,
+            "file" => &mut self.file,
+            "error" => &mut self.error,
+            "mount" => &mut self.mount,
+            "subvolume" => &mut self.subvolume,
+            "muted" => &mut self.muted,
+            "dim" => &mut self.dim,
+            "tabs" => &mut self.tabs,
+            "free" => &mut self.free,
+            "scanned" => &mut self.scanned,
+            "hidden" => &mut self.hidden,
+            "unknown" => &mut self.unknown,
+            "bar_text" => &mut self.bar_text,
+            _ => bail!("unknown color '{}'", name),
+        };
+        *field = color;
+        Ok(())
+    }
+}
+
+/// Parses color name (e.g. `lightyellow`), index in 256 color palette or `#rrggbb`
+fn parse_color(s: &str) -> Result<Color> {
+    if let Some(hex) = s.strip_prefix('#') {
+        let rgb = u32::from_str_radix(hex, 16)
+            .ok()
+            .filter(|_| hex.len() == 6)
+            .ok_or_else(|| anyhow!("invalid color '{}'", s))?;
+        return Ok(Color::Rgb((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8));
+    }
+    if let Ok(index) = s.parse() {
+        return Ok(Color::Indexed(index));
+    }
+    let color = match s.to_lowercase().replace(['-', '_'], "").as_str() {
+        "default" | "reset" => Color::Reset,
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "gray" | "grey" => Color::Gray,
+        "darkgray" | "darkgrey" => Color::DarkGray,
+        "lightred" => Color::LightRed,
+        "lightgreen" => Color::LightGreen,
+        "lightyellow" => Color::LightYellow,
+        "lightblue" => Color::LightBlue,
+        "lightmagenta" => Color::LightMagenta,
+        "lightcyan" => Color::LightCyan,
+        "white" => Color::White,
+        _ => bail!("invalid color '{}'", s),
+    };
+    Ok(color)
+}
@@ -0,0 +1,428 @@
+use byte_unit::Byte;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, StatefulWidget, Widget};
+
+use crate::theme::Theme;
+use crate::utils;
+
+/// Terminal cells are about twice as tall as wide,
+/// so heights are scaled to make rectangles look square
+const CELL_ASPECT: f64 = 2.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Move {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TreemapState {
+    /// Areas of items drawn during last render, `None` if item was too small
+    areas: Vec<Option<Rect>>,
+    /// Item that was drawn as selected during last render
+    selected: Option<usize>,
+}
+
+impl TreemapState {
+    /// Returns index of the first item that is drawn
+    pub fn first_visible(&self) -> Option<usize> {
+        self.areas.iter().position(Option::is_some)
+    }
+
+    /// Returns index of item that is drawn at given cell
+    pub fn item_at(&self, x: u16, y: u16) -> Option<usize> {
+        self.areas
+            .iter()
+            .position(|area| area.is_some_and(|a| utils::contains(a, x, y)))
+    }
+
+    /// Returns index of item that is next to selected one in given direction
+    ///
+    /// Items that overlap with selected one along the movement axis are preferred,
+    /// then the closest and the best aligned ones.
+    /// If selected item is not drawn, the first drawn item is returned
+    pub fn neighbor(&self, selected: usize, direction: Move) -> Option<usize> {
+        let from = match self.areas.get(selected).copied().flatten() {
+            Some(from) => from,
+            None => return self.first_visible(),
+        };
+        self.areas
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != selected)
+            .filter_map(|(i, area)| Some((i, (*area)?)))
+            .filter_map(|(i, area)| {
+                let (gap, overlap, offset) = match direction {
+                    Move::Left if area.right() <= from.left() => (
+                        from.left() - area.right(),
+                        overlap(area.y, area.bottom(), from.y, from.bottom()),
+                        center_offset(area.y, area.height, from.y, from.height),
+                    ),
+                    Move::Right if area.left() >= from.right() => (
+                        area.left() - from.right(),
+                        overlap(area.y, area.bottom(), from.y, from.bottom()),
+                        center_offset(area.y, area.height, from.y, from.height),
+                    ),
+                    Move::Up if area.bottom() <= from.top() => (
+                        from.top() - area.bottom(),
+                        overlap(area.x, area.right(), from.x, from.right()),
+                        center_offset(area.x, area.width, from.x, from.width),
+                    ),
+                    Move::Down if area.top() >= from.bottom() => (
+                        area.top() - from.bottom(),
+                        overlap(area.x, area.right(), from.x, from.right()),
+                        center_offset(area.x, area.width, from.x, from.width),
+                    ),
+                    _ => return None,
+                };
+                Some((i, (!overlap, gap, offset)))
+            })
+            .min_by_key(|(_, key)| *key)
+            .map(|(i, _)| i)
+    }
+
+    /// Returns item that was drawn as selected during last render
+    ///
+    /// It differs from requested selection if that item was too small to draw
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreemapItem {
+    name: String,
+    size: Byte,
+    is_dir: bool,
+}
+
+impl TreemapItem {
+    pub fn new(name: String, size: Byte, is_dir: bool) -> TreemapItem {
+        TreemapItem { name, size, is_dir }
+    }
+}
+
+/// Squarified treemap of items, each item is drawn as a rectangle with area
+/// proportional to its size
+#[derive(Debug, Clone)]
+pub struct Treemap<'a> {
+    block: Option<Block<'a>>,
+    items: Vec<TreemapItem>,
+    selected: Option<usize>,
+    simple_graphics: bool,
+    theme: Theme,
+}
+
+impl<'a> Treemap<'a> {
+    /// Creates treemap of given items, they should be sorted by size in descending order
+    pub fn new<T>(items: T) -> Treemap<'a>
+    where
+        T: Into<Vec<TreemapItem>>,
+    {
+        Treemap {
+            block: None,
+            items: items.into(),
+            selected: None,
+            simple_graphics: false,
+            theme: Theme::default(),
+        }
+    }
+
+    pub fn block(mut self, block: Block<'a>) -> Treemap<'a> {
+        self.block = Some(block);
+        self
+    }
+
+    pub fn select(mut self, selected: usize) -> Treemap<'a> {
+        self.selected = Some(selected);
+        self
+    }
+
+    pub fn simple_graphics(mut self, simple_graphics: bool) -> Treemap<'a> {
+        self.simple_graphics = simple_graphics;
+        self
+    }
+
+    pub fn theme(mut self, theme: Theme) -> Treemap<'a> {
+        self.theme = theme;
+        self
+    }
+
+    /// Draws single item inside given area
+    ///
+    /// The last column and row are drawn with half blocks (or ascii lines)
+    /// so neighbour rectangles are visually separated
+    fn draw_item(&self, item: &TreemapItem, area: Rect, color: Color, buf: &mut Buffer) {
+        let (fill, right, bottom, corner) = if self.simple_graphics {
+            (" ", "|", "-", "+")
+        } else {
+            ("█", "▌", "▀", "▘")
+        };
+        let fill_style = if self.simple_graphics {
+            Style::default().bg(color).fg(self.theme.background)
+        } else {
+            Style::default().fg(color)
+        };
+        let edge_style = Style::default().fg(color);
+
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                let is_right = area.width > 1 && x + 1 == area.right();
+                let is_bottom = area.height > 1 && y + 1 == area.bottom();
+                let (symbol, style) = match (is_right, is_bottom) {
+                    (true, true) => (corner, edge_style),
+                    (true, false) => (right, edge_style),
+                    (false, true) => (bottom, edge_style),
+                    (false, false) => (fill, fill_style),
+                };
+                buf.get_mut(x, y).set_symbol(symbol).set_style(style);
+            }
+        }
+
+        // labels are drawn over filled part only
+        let label_width = area.width.saturating_sub(1).max(1) as usize;
+        let label_style = Style::default().bg(color).fg(self.theme.background);
+        let lines = [item.name.clone(), utils::byte_to_str(item.size, 0)];
+        let label_height = area.height.saturating_sub(1).max(1);
+        for (i, line) in lines.iter().enumerate().take(label_height as usize) {
+            buf.set_stringn(area.x, area.y + i as u16, line, label_width, label_style);
+        }
+    }
+}
+
+impl<'a> StatefulWidget for Treemap<'a> {
+    type State = TreemapState;
+
+    fn render(mut self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        buf.set_style(area, Style::default());
+        let map_area = match self.block.take() {
+            Some(b) => {
+                let inner_area = b.inner(area);
+                b.render(area, buf);
+                inner_area
+            }
+            None => area,
+        };
+
+        state.areas = squarify(&self.items, map_area);
+        // hidden item can't be highlighted or moved from, so the first drawn one is selected
+        if self
+            .selected
+            .is_some_and(|i| state.areas.get(i).copied().flatten().is_none())
+        {
+            self.selected = state.first_visible();
+        }
+        state.selected = self.selected;
+
+        let palette = &self.theme.palette;
+        let mut dirs = 0;
+        for (i, item) in self.items.iter().enumerate() {
+            let color = if item.is_dir {
+                dirs += 1;
+                palette[(dirs - 1) % palette.len()]
+            } else {
+                self.theme.dim
+            };
+            if let Some(area) = state.areas[i] {
+                if self.selected == Some(i) {
+                    self.draw_item(item, area, self.theme.text, buf);
+                    buf.set_style(
+                        Rect { height: 1, ..area },
+                        Style::default().add_modifier(Modifier::BOLD),
+                    );
+                } else {
+                    self.draw_item(item, area, color, buf);
+                }
+            }
+        }
+    }
+}
+
+impl<'a> Widget for Treemap<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut state = TreemapState::default();
+        StatefulWidget::render(self, area, buf, &mut state);
+    }
+}
+
+/// Returns whether ranges `[a0, a1)` and `[b0, b1)` intersect
+fn overlap(a0: u16, a1: u16, b0: u16, b1: u16) -> bool {
+    a0 < b1 && b0 < a1
+}
+
+/// Returns distance between centers of two segments (doubled to avoid fractions)
+fn center_offset(a: u16, a_len: u16, b: u16, b_len: u16) -> u16 {
+    (2 * a + a_len).abs_diff(2 * b + b_len)
+}
+
+/// Splits area into rectangles with sizes proportional to sizes of items
+///
+/// Uses squarified algorithm (Bruls, Huizing, van Wijk) so rectangles are as
+/// close to squares as possible. Items that are smaller than a cell get `None`
+fn squarify(items: &[TreemapItem], area: Rect) -> Vec<Option<Rect>> {
+    let mut areas = vec![None; items.len()];
+    let total: f64 = items.iter().map(|i| i.size.get_bytes() as f64).sum();
+    if total <= 0.0 || area.width == 0 || area.height == 0 {
+        return areas;
+    }
+
+    // layout is calculated in units where cells are square
+    let (mut x, mut y) = (0.0, 0.0);
+    let (mut w, mut h) = (area.width as f64, area.height as f64 * CELL_ASPECT);
+    let scale = w * h / total;
+    let sizes: Vec<_> = items
+        .iter()
+        .map(|i| i.size.get_bytes() as f64 * scale)
+        .collect();
+
+    let mut start = 0;
+    // rounding errors can leave empty remaining area before all items are placed
+    while start < sizes.len() && sizes[start] > 0.0 && w > 0.0 && h > 0.0 {
+        let side = w.min(h);
+        // add items to row while it makes rectangles closer to squares
+        let mut end = start + 1;
+        while end < sizes.len()
+            && sizes[end] > 0.0
+            && worst_ratio(&sizes[start..end + 1], side) <= worst_ratio(&sizes[start..end], side)
+        {
+            end += 1;
+        }
+
+        let row_sum: f64 = sizes[start..end].iter().sum();
+        let thickness = row_sum / side;
+        let mut offset = 0.0;
+        for (i, size) in sizes.iter().enumerate().take(end).skip(start) {
+            let length = size / thickness;
+            let rect = if w >= h {
+                // row is placed as column at the left side
+                (x, y + offset, thickness, length)
+            } else {
+                (x + offset, y, length, thickness)
+            };
+            offset += length;
+            areas[i] = to_cells(rect, area);
+        }
+        if w >= h {
+            x += thickness;
+            w -= thickness;
+        } else {
+            y += thickness;
+            h -= thickness;
+        }
+        start = end;
+    }
+
+    areas
+}
+
+/// Converts rectangle in square units to cells inside area
+///
+/// Edges are rounded, so neighbour rectangles don't overlap and have no gaps
+fn to_cells((x, y, w, h): (f64, f64, f64, f64), area: Rect) -> Option<Rect> {
+    let x0 = x.round() as u16;
+    let x1 = ((x + w).round() as u16).min(area.width);
+    let y0 = (y / CELL_ASPECT).round() as u16;
+    let y1 = (((y + h) / CELL_ASPECT).round() as u16).min(area.height);
+    (x1 > x0 && y1 > y0).then(|| Rect::new(area.x + x0, area.y + y0, x1 - x0, y1 - y0))
+}
+
+/// Returns the worst aspect ratio of rectangles if items are placed in a row along side
+fn worst_ratio(row: &[f64], side: f64) -> f64 {
+    let sum: f64 = row.iter().sum();
+    let max = row.iter().cloned().fold(f64::MIN, f64::max);
+    let min = row.iter().cloned().fold(f64::MAX, f64::min);
+    let side2 = side * side;
+    let sum2 = sum * sum;
+    (side2 * max / sum2).max(sum2 / (side2 * min))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items(sizes: &[u64]) -> Vec<TreemapItem> {
+        sizes
+            .iter()
+            .map(|&size| TreemapItem::new(size.to_string(), Byte::from_bytes(size), true))
+            .collect()
+    }
+
+    #[test]
+    fn squarify_tiles_area() {
+        let area = Rect::new(3, 2, 40, 20);
+        let areas = squarify(&items(&[600, 400, 300, 200, 200, 100, 100]), area);
+        let areas: Vec<_> = areas.into_iter().map(|a| a.unwrap()).collect();
+
+        for (i, a) in areas.iter().enumerate() {
+            assert_eq!(a.intersection(area), *a, "{:?} is outside", a);
+            for b in &areas[i + 1..] {
+                assert!(!a.intersects(*b), "{:?} overlaps {:?}", a, b);
+            }
+        }
+        let covered: u32 = areas.iter().map(|a| a.area() as u32).sum();
+        assert_eq!(covered, area.area() as u32);
+    }
+
+    #[test]
+    fn squarify_small_items() {
+        let area = Rect::new(0, 0, 10, 5);
+        let areas = squarify(&items(&[1_000_000, 1, 1, 0]), area);
+        assert_eq!(areas[0], Some(area));
+        assert_eq!(&areas[1..], &[None, None, None]);
+
+        assert_eq!(squarify(&items(&[0, 0]), area), vec![None, None]);
+        assert_eq!(squarify(&items(&[1]), Rect::new(0, 0, 0, 5)), vec![None]);
+    }
+
+    #[test]
+    fn neighbor() {
+        // +----+----+
+        // |    | 1  |
+        // | 0  +----+
+        // |    | 2  |
+        // +----+----+
+        let state = TreemapState {
+            areas: vec![
+                Some(Rect::new(0, 0, 4, 4)),
+                Some(Rect::new(4, 0, 4, 1)),
+                Some(Rect::new(4, 1, 4, 3)),
+                None,
+            ],
+            selected: None,
+        };
+        // the best aligned one is preferred
+        assert_eq!(state.neighbor(0, Move::Right), Some(2));
+        assert_eq!(state.neighbor(0, Move::Left), None);
+        assert_eq!(state.neighbor(0, Move::Down), None);
+        assert_eq!(state.neighbor(1, Move::Left), Some(0));
+        assert_eq!(state.neighbor(1, Move::Down), Some(2));
+        assert_eq!(state.neighbor(1, Move::Right), None);
+        assert_eq!(state.neighbor(2, Move::Up), Some(1));
+        assert_eq!(state.neighbor(2, Move::Left), Some(0));
+        // moving from hidden item selects the first drawn one
+        assert_eq!(state.neighbor(3, Move::Left), Some(0));
+        assert_eq!(state.neighbor(4, Move::Down), Some(0));
+        assert_eq!(TreemapState::default().neighbor(0, Move::Up), None);
+        assert_eq!(state.item_at(5, 0), Some(1));
+        assert_eq!(state.item_at(8, 0), None);
+    }
+
+    #[test]
+    fn hidden_selection() {
+        let mut state = TreemapState::default();
+        let mut render = |sizes: &[u64], selected| {
+            let area = Rect::new(0, 0, 10, 5);
+            let treemap = Treemap::new(items(sizes)).select(selected);
+            StatefulWidget::render(treemap, area, &mut Buffer::empty(area), &mut state);
+            state.selected()
+        };
+        assert_eq!(render(&[1_000_000, 1], 1), Some(0));
+        assert_eq!(render(&[600, 400], 1), Some(1));
+        // nothing can be selected if no item is drawn
+        assert_eq!(render(&[0], 0), None);
+    }
+}
@@ -1,19 +1,26 @@
 use byte_unit::Byte;
 use ratatui::backend::Backend;
 use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
-use ratatui::style::{Color, Modifier, Style};
+use ratatui::style::{Modifier, Style};
 use ratatui::text::{Span, Spans};
-use ratatui::widgets::{Block, BorderType, Borders, Paragraph, Tabs};
+use ratatui::widgets::{Block, BorderType, Borders, List, ListItem, Paragraph, Tabs};
 use ratatui::Frame;
+use unicode_width::UnicodeWidthStr;
 
-use diskscan::SnapshotConfig;
+use diskscan::{EntryPath, ScanError};
 
-use crate::app::{App, FilesApp, Screen};
-use crate::file_list::{FileList, FileListItem};
+use crate::app::{App, FilesApp, FilesView, Screen};
+use crate::file_list::{Column, FileList, FileListItem};
+use crate::keymap::{Action, Key, Keymap};
 use crate::log_list::LogList;
 use crate::progressbar::{BarItem, ProgressBar};
+use crate::theme::Theme;
+use crate::treemap::{Treemap, TreemapItem};
 use crate::utils;
 
+/// Name of virtual entry with deleted files that are still open
+const DELETED_ENTRY: &str = "<deleted but open files>";
+
 pub fn draw(frame: &mut Frame<impl Backend>, app: &mut App, simple_graphics: bool) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -23,19 +30,33 @@ pub fn draw(frame: &mut Frame<impl Backend>, app: &mut App, simple_graphics: boo
     render_menu(frame, chunks[0], app);
 
     match app.screen {
-        Screen::Help => render_controls(frame, chunks[1]),
+        Screen::Help => render_controls(frame, chunks[1], &app.keymap, &app.theme),
         Screen::Files if app.files.is_some() => render_files(
             frame,
             chunks[1],
             app.files.as_mut().unwrap(),
+            &app.columns,
+            &app.theme,
             simple_graphics,
         ),
+        Screen::Errors if app.files.is_some() => {
+            render_errors(frame, chunks[1], app.files.as_mut().unwrap(), &app.theme)
+        }
+        Screen::Deleted if app.files.is_some() => render_deleted(
+            frame,
+            chunks[1],
+            app.files.as_mut().unwrap(),
+            &app.keymap,
+            &app.theme,
+        ),
         Screen::Log => render_log(frame, chunks[1], app),
         _ => {}
     }
 
     if let Some(dialog) = app.dialog.as_ref() {
         let (w, h) = dialog.size(app);
+        // dialog can't be larger than screen
+        let (w, h) = (w.min(chunks[1].width), h.min(chunks[1].height));
         let x = chunks[1].x + chunks[1].width.saturating_sub(w) / 2;
         let y = chunks[1].y + chunks[1].height.saturating_sub(h) / 2;
         let size = Rect::new(x, y, w, h);
@@ -43,53 +64,196 @@ pub fn draw(frame: &mut Frame<impl Backend>, app: &mut App, simple_graphics: boo
     }
 }
 
-fn render_controls(frame: &mut Frame<impl Backend>, rect: Rect) {
-    let lines = vec![
+fn render_controls(frame: &mut Frame<impl Backend>, rect: Rect, keymap: &Keymap, theme: &Theme) {
+    let header = vec![
         Spans::from(vec![Span::raw("Welcome to")]),
         Spans::from(vec![Span::styled(
             "spacedisplay",
-            Style::default().fg(Color::LightYellow),
+            Style::default().fg(theme.accent),
         )]),
         Spans::from(vec![Span::raw("")]),
         Spans::from(vec![Span::raw("Press:")]),
-        Spans::from(vec![Span::raw("'H' or 'F1' to return to this screen")]),
-        Spans::from(vec![Span::raw("'N' to start a new scan")]),
-        Spans::from(vec![Span::raw("'R' or 'F5' to rescan opened directory")]),
-        Spans::from(vec![Span::raw("'F' to open files list")]),
-        Spans::from(vec![Span::raw("'Up' and 'Down' to move inside list")]),
-        Spans::from(vec![Span::raw(
-            "'Enter' or 'Right' to open selected directory",
-        )]),
-        Spans::from(vec![Span::raw("'D' to delete files")]),
+    ];
+    let footer = vec![
+        Spans::from(vec![Span::raw("")]),
         Spans::from(vec![Span::raw(
-            "'Esc', 'Backspace' or 'Left' to navigate up",
+            "Mouse: click to select, double click to open, wheel to scroll, click tabs and buttons",
         )]),
-        Spans::from(vec![Span::raw("'Q' to quit")]),
     ];
-
-    let text_height = lines.len() as u16;
-
-    let home = Paragraph::new(lines).alignment(Alignment::Center);
+    let bindings: Vec<_> = keymap
+        .get_bindings()
+        .into_iter()
+        .map(|(action, keys)| {
+            Spans::from(vec![Span::raw(format!(
+                "{} to {}",
+                keys_to_str(&keys),
+                action.description()
+            ))])
+        })
+        .collect();
 
     frame.render_widget(
         Block::default()
             .borders(Borders::ALL)
-            .style(Style::default().fg(Color::White))
+            .style(Style::default().fg(theme.text))
             .border_type(BorderType::Plain),
         rect,
     );
-    let mut rect = frame.size();
-    if rect.height >= text_height {
-        rect.y = (rect.height - text_height) / 2;
-        rect.height = text_height;
+    let rect = Rect {
+        x: rect.x + 1,
+        y: rect.y + 1,
+        width: rect.width.saturating_sub(2),
+        height: rect.height.saturating_sub(2),
+    };
+
+    // bindings are split in two columns if they don't fit
+    let extra_height = header.len() + footer.len();
+    let columns = if extra_height + bindings.len() <= rect.height as usize {
+        1
+    } else {
+        2
+    };
+    let column_height = bindings.len().div_ceil(columns);
+    let text_height = (extra_height + column_height) as u16;
+    let y = rect.y + rect.height.saturating_sub(text_height) / 2;
+
+    let header_height = header.len() as u16;
+    let header_rect = Rect::new(rect.x, y, rect.width, header_height).intersection(rect);
+    frame.render_widget(
+        Paragraph::new(header).alignment(Alignment::Center),
+        header_rect,
+    );
+
+    let columns_rect =
+        Rect::new(rect.x, y + header_height, rect.width, column_height as u16).intersection(rect);
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(vec![Constraint::Ratio(1, columns as u32); columns])
+        .split(columns_rect);
+    for (lines, chunk) in bindings.chunks(column_height.max(1)).zip(chunks.iter()) {
+        frame.render_widget(
+            Paragraph::new(lines.to_vec()).alignment(Alignment::Center),
+            *chunk,
+        );
+    }
+
+    let footer_rect = Rect::new(
+        rect.x,
+        columns_rect.y + column_height as u16,
+        rect.width,
+        footer.len() as u16,
+    )
+    .intersection(rect);
+    frame.render_widget(
+        Paragraph::new(footer).alignment(Alignment::Center),
+        footer_rect,
+    );
+}
+
+/// Converts keys to text like `'h' or 'F1'`
+fn keys_to_str(keys: &[Key]) -> String {
+    let keys: Vec<_> = keys.iter().map(|k| format!("'{}'", k)).collect();
+    match keys.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} or {}", rest.join(", "), last),
+        _ => keys.join(""),
     }
-    frame.render_widget(home, rect);
+}
+
+fn render_deleted(
+    frame: &mut Frame<impl Backend>,
+    rect: Rect,
+    app: &mut FilesApp,
+    keymap: &Keymap,
+    theme: &Theme,
+) {
+    let keys = keymap.get_keys(Action::Rescan);
+    let hint = if app.is_searching_deleted() {
+        ", searching…".to_string()
+    } else if keys.is_empty() {
+        String::new()
+    } else {
+        format!(", press {} to refresh", keys_to_str(&keys))
+    };
+    let title = format!(
+        " Deleted but open files: {} ({}){} ",
+        app.deleted.entries.len(),
+        utils::byte_to_str(app.get_deleted_size(), 0),
+        hint
+    );
+    let items: Vec<_> = app
+        .deleted
+        .entries
+        .iter()
+        .map(|file| {
+            ListItem::new(Spans::from(vec![
+                Span::styled(
+                    format!("{:>10}  ", utils::byte_to_str(file.size, 0)),
+                    Style::default().fg(theme.error),
+                ),
+                Span::styled(
+                    format!("{:<24}", format!("{} ({})", file.process, file.pid)),
+                    Style::default().fg(theme.mount),
+                ),
+                Span::styled(file.path.clone(), Style::default().fg(theme.accent)),
+            ]))
+        })
+        .collect();
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .style(Style::default().fg(theme.text))
+        .title(title)
+        .border_type(BorderType::Plain);
+    app.deleted.set_area(block.inner(rect));
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+        .highlight_symbol(" > ");
+    frame.render_stateful_widget(list, rect, &mut app.deleted.list_state);
+}
+
+fn render_errors(frame: &mut Frame<impl Backend>, rect: Rect, app: &mut FilesApp, theme: &Theme) {
+    let entries = &app.errors.entries;
+    let count = |kind: ScanError| entries.iter().filter(|(_, e)| *e == kind).count();
+    let title = format!(
+        " Scan errors: {} (permission denied: {}, not found: {}, I/O: {}) ",
+        entries.len(),
+        count(ScanError::PermissionDenied),
+        count(ScanError::NotFound),
+        count(ScanError::Io),
+    );
+    let items: Vec<_> = entries
+        .iter()
+        .map(|(path, error)| {
+            ListItem::new(Spans::from(vec![
+                Span::styled(
+                    format!("{:<20}", error.to_string()),
+                    Style::default().fg(theme.error),
+                ),
+                Span::styled(path.to_string(), Style::default().fg(theme.accent)),
+            ]))
+        })
+        .collect();
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .style(Style::default().fg(theme.text))
+        .title(title)
+        .border_type(BorderType::Plain);
+    app.errors.set_area(block.inner(rect));
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+        .highlight_symbol(" > ");
+    frame.render_stateful_widget(list, rect, &mut app.errors.list_state);
 }
 
 fn render_files(
     frame: &mut Frame<impl Backend>,
     rect: Rect,
     app: &mut FilesApp,
+    columns: &[Column],
+    theme: &Theme,
     simple_graphics: bool,
 ) {
     let chunks = Layout::default()
@@ -97,84 +261,184 @@ fn render_files(
         .constraints([Constraint::Min(10), Constraint::Length(1)].as_ref())
         .split(rect);
 
-    let list = create_files_list(app, simple_graphics);
-    let progressbar = create_progressbar(app);
-
-    frame.render_stateful_widget(list, chunks[0], &mut app.file_list_state);
+    let progressbar = create_progressbar(app, theme);
+    app.breadcrumb_areas = breadcrumb_areas(&app.current_path, chunks[0]);
+    match app.view {
+        FilesView::List | FilesView::Tree => {
+            let list = create_files_list(app, columns, theme, simple_graphics);
+            frame.render_stateful_widget(list, chunks[0], &mut app.file_list_state);
+        }
+        FilesView::Treemap => {
+            let treemap = create_treemap(app, theme, simple_graphics);
+            frame.render_stateful_widget(treemap, chunks[0], &mut app.treemap_state);
+            if let Some(selected) = app.treemap_state.selected() {
+                app.file_list_state.select(selected);
+            }
+        }
+    }
     frame.render_widget(progressbar, chunks[1]);
 }
 
 fn render_log(frame: &mut Frame<impl Backend>, rect: Rect, app: &mut App) {
     let list = LogList::new(&app.logs_app.entries)
+        .theme(app.theme.clone())
         .block(Block::default().title("Logs").borders(Borders::ALL));
     frame.render_stateful_widget(list, rect, &mut app.logs_app.list_state);
 }
 
-fn render_menu(frame: &mut Frame<impl Backend>, rect: Rect, app: &App) {
+fn render_menu(frame: &mut Frame<impl Backend>, rect: Rect, app: &mut App) {
     let titles = app.tab_titles();
+
+    // the same layout as in tabs widget: padding around each title and divider between them
+    app.tab_areas.clear();
+    let mut x = rect.x;
+    for (title, action) in &titles {
+        let width = title.width() as u16;
+        let area = Rect::new(x.saturating_add(1), rect.y, width, 1);
+        // tabs that don't fit are not drawn
+        if area.intersects(rect) {
+            app.tab_areas.push((area.intersection(rect), *action));
+        }
+        x = x.saturating_add(width + 3);
+    }
+
     let titles = titles
         .iter()
-        .map(|t| {
-            let (first, rest) = t.split_at(1);
+        .map(|(title, action)| {
+            let (first, rest) = title.split_at(1);
+            // first letter is underlined only if it is a key of this tab
+            let key = first.to_lowercase().chars().next().map(Key::from_char);
+            let is_key = key.is_some_and(|k| app.keymap.get_keys(*action).contains(&k));
+            let first_style = if is_key {
+                Style::default()
+                    .fg(app.theme.text)
+                    .add_modifier(Modifier::UNDERLINED)
+            } else {
+                Style::default().fg(app.theme.text)
+            };
             Spans::from(vec![
-                Span::styled(
-                    first,
-                    Style::default()
-                        .fg(Color::White)
-                        .add_modifier(Modifier::UNDERLINED),
-                ),
-                Span::styled(rest, Style::default().fg(Color::White)),
+                Span::styled(first, first_style),
+                Span::styled(rest, Style::default().fg(app.theme.text)),
             ])
         })
         .collect();
 
     let tabs = Tabs::new(titles)
         .select(app.selected_tab())
-        .style(Style::default().fg(Color::Cyan))
+        .style(Style::default().fg(app.theme.tabs))
         .highlight_style(
             Style::default()
                 .add_modifier(Modifier::BOLD)
-                .bg(Color::Black),
+                .bg(app.theme.background),
         );
     frame.render_widget(tabs, rect);
 }
 
-fn create_files_list(app: &mut FilesApp, simple_graphics: bool) -> FileList<'static> {
+fn create_files_list(
+    app: &mut FilesApp,
+    columns: &[Column],
+    theme: &Theme,
+    simple_graphics: bool,
+) -> FileList<'static> {
     let tree = app
         .scanner
-        .get_tree(
-            &app.current_path,
-            SnapshotConfig {
-                max_depth: 1,
-                min_size: 0,
-            },
-        )
+        .get_tree(&app.current_path, app.get_snapshot_config())
         .unwrap();
-    let files: Vec<_> = tree.get_root().iter().collect();
-    if app.file_list_state.selected() >= files.len() && !files.is_empty() {
-        app.file_list_state.select(files.len() - 1);
+    let unwatched = tree.get_root().is_unwatched();
+    let files: Vec<_> = if app.view == FilesView::Tree {
+        app.get_tree_rows(tree.get_root())
+            .into_iter()
+            .map(|row| {
+                let expanded = row.entry.is_dir().then_some(row.is_expanded);
+                let prefix = tree_prefix(&row.is_last, expanded, simple_graphics);
+                (row.entry, prefix)
+            })
+            .collect()
+    } else {
+        tree.get_root()
+            .iter()
+            .map(|file| (file, String::new()))
+            .collect()
+    };
+    let items_count = files.len() + usize::from(app.has_deleted_entry());
+    if app.file_list_state.selected() >= items_count && items_count > 0 {
+        app.file_list_state.select(items_count - 1);
     }
 
-    let items: Vec<_> = files
+    let mut items: Vec<_> = files
         .into_iter()
-        .map(|file| {
-            FileListItem::new(file.get_name().to_string(), file.get_size()).style(
-                if file.is_dir() {
-                    Style::default().fg(Color::LightYellow)
-                } else {
-                    Style::default().fg(Color::LightBlue)
-                },
-            )
+        .map(|(file, prefix)| {
+            // mount points are shown with type of mounted filesystem
+            // and btrfs subvolumes with their kind
+            let (name, style) = match (file.get_mount(), file.get_subvolume()) {
+                (Some(mount), _) if mount.crossed => (
+                    format!("{} [{}]", file.get_name(), mount.fs_type),
+                    Style::default().fg(theme.mount),
+                ),
+                (Some(mount), _) => (
+                    format!("{} [{}, skipped]", file.get_name(), mount.fs_type),
+                    Style::default().fg(theme.muted),
+                ),
+                (None, Some(subvolume)) if subvolume.skipped => (
+                    format!("{} [snapshot, skipped]", file.get_name()),
+                    Style::default().fg(theme.muted),
+                ),
+                (None, Some(subvolume)) => (
+                    format!(
+                        "{} [{}]",
+                        file.get_name(),
+                        if subvolume.is_snapshot {
+                            "snapshot"
+                        } else {
+                            "subvolume"
+                        }
+                    ),
+                    Style::default().fg(theme.subvolume),
+                ),
+                (None, None) if file.is_dir() => (
+                    file.get_name().to_string(),
+                    Style::default().fg(theme.accent),
+                ),
+                (None, None) => (file.get_name().to_string(), Style::default().fg(theme.file)),
+            };
+            FileListItem::new(name, file.get_size())
+                .prefix(prefix)
+                .style(style)
+                .unwatched(file.is_unwatched())
+                .incomplete(file.is_incomplete())
+                .error(file.get_error().is_some())
         })
         .collect();
+    if app.has_deleted_entry() {
+        // virtual entry is not part of tree and opens list of deleted files
+        items.push(
+            FileListItem::new(DELETED_ENTRY.to_string(), app.get_deleted_size())
+                .style(Style::default().fg(theme.error)),
+        );
+    }
 
-    let list = FileList::new(items)
-        .simple_graphics(simple_graphics)
+    let mut list = FileList::new(items)
+        .columns(columns.to_vec())
+        .theme(theme.clone())
+        .simple_graphics(simple_graphics);
+    if app.view == FilesView::Tree {
+        // nested entries are part of their parents, so only opened directory is full bar
+        list = list.total_size(tree.get_root().get_size());
+    }
+    let list = list
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .style(Style::default().fg(Color::White))
-                .title(format!(" {} ", app.current_path))
+                .style(Style::default().fg(theme.text))
+                .title(breadcrumb_title(
+                    &app.current_path,
+                    if unwatched {
+                        " (changes not tracked) "
+                    } else {
+                        " "
+                    },
+                    theme,
+                ))
                 .border_type(BorderType::Plain),
         )
         .highlight_style(Style::default().add_modifier(Modifier::BOLD));
@@ -182,7 +446,119 @@ fn create_files_list(app: &mut FilesApp, simple_graphics: bool) -> FileList<'sta
     list
 }
 
-fn create_progressbar(app: &FilesApp) -> ProgressBar {
+/// Returns parts of opened path: separator before part, its name and path of directory
+fn breadcrumbs(path: &EntryPath) -> Vec<(&'static str, String, EntryPath)> {
+    let parts = path.parts();
+    let mut crumb = EntryPath::new(parts[0].clone());
+    let mut crumbs = vec![("", parts[0].clone(), crumb.clone())];
+    for part in &parts[1..] {
+        // root like `/` already ends with separator
+        let separator = if crumbs.len() == 1 && parts[0].ends_with(std::path::is_separator) {
+            ""
+        } else {
+            std::path::MAIN_SEPARATOR_STR
+        };
+        crumb.join(part.clone());
+        crumbs.push((separator, part.clone(), crumb.clone()));
+    }
+    crumbs
+}
+
+/// Returns areas of path parts in title of block drawn at given area
+///
+/// Title starts after left border and a space, parts outside of block are skipped
+fn breadcrumb_areas(path: &EntryPath, area: Rect) -> Vec<(Rect, EntryPath)> {
+    let mut areas = vec![];
+    let mut x = area.x.saturating_add(2);
+    let right = area.right().saturating_sub(1);
+    for (separator, name, crumb) in breadcrumbs(path) {
+        x = x.saturating_add(separator.width() as u16);
+        let width = (name.width() as u16).min(right.saturating_sub(x));
+        if width > 0 {
+            areas.push((Rect::new(x, area.y, width, 1), crumb));
+        }
+        x = x.saturating_add(name.width() as u16);
+    }
+    areas
+}
+
+/// Creates title of files block where each part of opened path can be clicked
+fn breadcrumb_title(path: &EntryPath, suffix: &'static str, theme: &Theme) -> Spans<'static> {
+    let text = Style::default().fg(theme.text);
+    let crumbs = breadcrumbs(path);
+    let last = crumbs.len() - 1;
+    let mut spans = vec![Span::styled(" ", text)];
+    for (i, (separator, name, _)) in crumbs.into_iter().enumerate() {
+        spans.push(Span::styled(separator, text));
+        spans.push(Span::styled(
+            name,
+            if i == last {
+                text.add_modifier(Modifier::BOLD)
+            } else {
+                text
+            },
+        ));
+    }
+    spans.push(Span::styled(suffix, text));
+    Spans::from(spans)
+}
+
+/// Returns indentation guides and expand marker of entry in tree view
+///
+/// Marker is shown only for directories, `expanded` is `None` for files
+fn tree_prefix(is_last: &[bool], expanded: Option<bool>, simple_graphics: bool) -> String {
+    let (line, branch, last_branch, open, closed) = if simple_graphics {
+        ("|  ", "|- ", "`- ", "- ", "+ ")
+    } else {
+        ("│  ", "├─ ", "└─ ", "▾ ", "▸ ")
+    };
+    let mut prefix = String::new();
+    if let Some((&last, parents)) = is_last.split_last() {
+        for &parent_last in parents {
+            prefix.push_str(if parent_last { "   " } else { line });
+        }
+        prefix.push_str(if last { last_branch } else { branch });
+    }
+    prefix.push_str(match expanded {
+        Some(true) => open,
+        Some(false) => closed,
+        None => "  ",
+    });
+    prefix
+}
+
+fn create_treemap(app: &mut FilesApp, theme: &Theme, simple_graphics: bool) -> Treemap<'static> {
+    let items: Vec<_> = app
+        .snapshot
+        .as_ref()
+        .map(|snapshot| {
+            snapshot
+                .get_root()
+                .iter()
+                .map(|file| {
+                    TreemapItem::new(file.get_name().to_string(), file.get_size(), file.is_dir())
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    if app.file_list_state.selected() >= items.len() && !items.is_empty() {
+        app.file_list_state.select(items.len() - 1);
+    }
+
+    Treemap::new(items)
+        .simple_graphics(simple_graphics)
+        .theme(theme.clone())
+        .select(app.file_list_state.selected())
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().fg(theme.text))
+                .title(breadcrumb_title(&app.current_path, " (treemap) ", theme))
+                .border_type(BorderType::Plain),
+        )
+}
+
+fn create_progressbar(app: &FilesApp, theme: &Theme) -> ProgressBar {
     let mut items = vec![];
     let stats = &app.stats;
     let used = stats.used_size.get_bytes();
@@ -192,16 +568,16 @@ fn create_progressbar(app: &FilesApp) -> ProgressBar {
         items.push(BarItem {
             label: utils::byte_to_str(current, 1),
             weight: current.get_bytes() as f64,
-            bg: Color::LightBlue,
-            fg: Color::White,
+            bg: theme.scanned,
+            fg: theme.bar_text,
             min_ratio: None,
         });
         if invisible.get_bytes() > 0 {
             items.push(BarItem {
                 label: utils::byte_to_str(invisible, 1),
                 weight: invisible.get_bytes() as f64,
-                bg: Color::Blue,
-                fg: Color::White,
+                bg: theme.hidden,
+                fg: theme.bar_text,
                 min_ratio: None,
             });
         }
@@ -219,8 +595,8 @@ fn create_progressbar(app: &FilesApp) -> ProgressBar {
                     } else {
                         0.0
                     },
-                    bg: Color::Gray,
-                    fg: Color::Black,
+                    bg: theme.unknown,
+                    fg: theme.background,
                     min_ratio: None,
                 });
             }
@@ -232,8 +608,8 @@ fn create_progressbar(app: &FilesApp) -> ProgressBar {
             } else {
                 0.0
             },
-            bg: Color::Green,
-            fg: Color::White,
+            bg: theme.free,
+            fg: theme.bar_text,
             min_ratio: None,
         });
     }
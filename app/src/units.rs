@@ -0,0 +1,139 @@
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use anyhow::{bail, Result};
+use byte_unit::{Byte, ByteUnit};
+use num_format::{CustomFormat, Grouping};
+
+/// Maximum number of fractional digits in sizes
+pub const MAX_PRECISION: usize = 6;
+
+static SIZE_FORMAT: OnceLock<SizeFormat> = OnceLock::new();
+
+/// Format of exact sizes, thousands are separated by space
+static BYTES_FORMAT: OnceLock<CustomFormat> = OnceLock::new();
+
+/// Units that are used to show sizes
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Units {
+    /// Powers of 1024 (KiB, MiB, ...)
+    #[default]
+    Binary,
+    /// Powers of 1000 (kB, MB, ...)
+    Decimal,
+    /// Exact number of bytes with thousands separators
+    Bytes,
+}
+
+impl FromStr for Units {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "binary" => Ok(Units::Binary),
+            "decimal" => Ok(Units::Decimal),
+            "bytes" => Ok(Units::Bytes),
+            _ => bail!("unknown units '{}', available: binary, decimal, bytes", s),
+        }
+    }
+}
+
+/// How sizes are converted to strings in UI and output
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SizeFormat {
+    pub units: Units,
+    /// Number of fractional digits, not used for exact bytes
+    pub precision: usize,
+}
+
+impl Default for SizeFormat {
+    fn default() -> Self {
+        SizeFormat {
+            units: Units::default(),
+            precision: 2,
+        }
+    }
+}
+
+impl SizeFormat {
+    pub fn format(&self, size: Byte) -> String {
+        let binary = match self.units {
+            Units::Binary => true,
+            Units::Decimal => false,
+            Units::Bytes => {
+                let format = BYTES_FORMAT.get_or_init(|| {
+                    CustomFormat::builder()
+                        .grouping(Grouping::Standard)
+                        .separator(" ")
+                        .build()
+                        .expect("format is valid")
+                });
+                let mut buf = num_format::Buffer::new();
+                buf.write_formatted(&size.get_bytes(), format);
+                return format!("{} B", buf.as_str());
+            }
+        };
+        let adjusted = size.get_appropriate_unit(binary);
+        match adjusted.get_unit() {
+            // SI symbol of kilo is lowercase
+            ByteUnit::KB => format!("{:.*} kB", self.precision, adjusted.get_value()),
+            _ => adjusted.format(self.precision),
+        }
+    }
+
+    /// Returns format that was set at startup or default one
+    pub fn global() -> SizeFormat {
+        SIZE_FORMAT.get().copied().unwrap_or_default()
+    }
+
+    /// Sets format that is used by all sizes, can be called only once
+    pub fn set_global(format: SizeFormat) {
+        SIZE_FORMAT.set(format).expect("size format is already set");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use byte_unit::Byte;
+
+    use crate::units::{SizeFormat, Units};
+
+    fn format(units: Units, precision: usize, bytes: u64) -> String {
+        SizeFormat { units, precision }.format(Byte::from_bytes(bytes))
+    }
+
+    #[test]
+    fn binary() {
+        assert_eq!(format(Units::Binary, 2, 0), "0 B");
+        assert_eq!(format(Units::Binary, 2, 1023), "1023 B");
+        assert_eq!(format(Units::Binary, 2, 1536), "1.50 KiB");
+        assert_eq!(format(Units::Binary, 0, 1536), "2 KiB");
+        assert_eq!(format(Units::Binary, 1, 5 * 1024 * 1024 * 1024), "5.0 GiB");
+    }
+
+    #[test]
+    fn decimal() {
+        assert_eq!(format(Units::Decimal, 2, 999), "999 B");
+        // SI symbol of kilo is lowercase
+        assert_eq!(format(Units::Decimal, 2, 1500), "1.50 kB");
+        assert_eq!(format(Units::Decimal, 0, 1500), "2 kB");
+        assert_eq!(format(Units::Decimal, 3, 2_345_678), "2.346 MB");
+        assert_eq!(format(Units::Decimal, 6, 1_500_000_000), "1.500000 GB");
+    }
+
+    #[test]
+    fn bytes() {
+        // precision is ignored for exact sizes
+        assert_eq!(format(Units::Bytes, 2, 0), "0 B");
+        assert_eq!(format(Units::Bytes, 2, 999), "999 B");
+        assert_eq!(format(Units::Bytes, 0, 1000), "1 000 B");
+        assert_eq!(format(Units::Bytes, 2, 1_234_567_890), "1 234 567 890 B");
+    }
+
+    #[test]
+    fn parse_units() {
+        assert_eq!("binary".parse::<Units>().unwrap(), Units::Binary);
+        assert_eq!("bytes".parse::<Units>().unwrap(), Units::Bytes);
+        assert!("Binary".parse::<Units>().is_err());
+    }
+}
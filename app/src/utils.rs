@@ -1,14 +1,48 @@
+use std::path::{Component, Path, PathBuf};
+
 use byte_unit::Byte;
+use ratatui::layout::Rect;
 use ratatui::symbols;
 
+use crate::units::SizeFormat;
+
+/// Converts size to string with units selected by user
 pub fn byte_to_str(b: Byte, padding: usize) -> String {
     format!(
         "{0}{1}{0}",
         " ".repeat(padding),
-        b.get_appropriate_unit(true)
+        SizeFormat::global().format(b)
     )
 }
 
+/// Encodes data with standard base64 alphabet and padding
+pub fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let triple = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let index = (triple >> (18 - 6 * i)) & 0x3f;
+                encoded.push(ALPHABET[index as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Checks whether cell at given column and row is inside area
+pub fn contains(area: Rect, x: u16, y: u16) -> bool {
+    x >= area.left() && x < area.right() && y >= area.top() && y < area.bottom()
+}
+
 pub fn get_unicode_block<'a>(frac: f64) -> &'a str {
     match (frac * 8.0).round() as u16 {
         1 => symbols::block::ONE_EIGHTH,
@@ -22,3 +56,54 @@ pub fn get_unicode_block<'a>(frac: f64) -> &'a str {
         _ => " ",
     }
 }
+
+/// Removes `.` and `..` components of path without accessing file system
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::utils;
+
+    #[test]
+    fn base64() {
+        // test vectors from RFC 4648
+        let vectors = [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ];
+        for (data, encoded) in vectors {
+            assert_eq!(utils::base64(data.as_bytes()), encoded);
+        }
+        assert_eq!(utils::base64(&[0xfb, 0xff, 0xbf]), "+/+/");
+        assert_eq!(utils::base64("/tmp/ü".as_bytes()), "L3RtcC/DvA==");
+    }
+
+    #[test]
+    fn normalize_path() {
+        let normalize = |path: &str| utils::normalize_path(Path::new(path));
+        assert_eq!(normalize("/a/b/../c"), Path::new("/a/c"));
+        assert_eq!(normalize("/a/./b/"), Path::new("/a/b"));
+        assert_eq!(normalize("/a/b/../../.."), Path::new("/"));
+        assert_eq!(normalize("/.."), Path::new("/"));
+        assert_eq!(normalize("/a//b/."), Path::new("/a/b"));
+    }
+}
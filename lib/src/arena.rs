@@ -1,6 +1,6 @@
 use std::num::NonZeroU32;
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct Id(NonZeroU32);
 
 impl Id {
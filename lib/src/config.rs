@@ -0,0 +1,149 @@
+//! Options of scanner that can be loaded from TOML and helpers to deserialize them
+//!
+//! Helpers are public so programs that embed these options in their own config
+//! parse values the same way
+
+use std::time::Duration;
+
+use serde::de::{self, Deserializer};
+use serde::Deserialize;
+
+use crate::watcher::MIN_POLL_INTERVAL;
+
+/// Options of [`ScannerBuilder`](crate::ScannerBuilder) that can be loaded from TOML
+///
+/// Options that are not set keep values of builder, see [`ScannerBuilder::config`](crate::ScannerBuilder::config).
+/// Lists can be written as single string. Durations are in milliseconds:
+///
+/// ```toml
+/// one_file_system = true
+/// cross = ["/home"]
+/// real_fs = ["fuse.sshfs"]
+/// pseudo_fs = []
+/// skip_snapshots = false
+/// exclude = [".git", "/var/cache"]
+/// poll_interval = 5000
+/// rescan_delay = 200
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScannerConfig {
+    /// Mounted filesystems that are scanned, implies `one_file_system`
+    #[serde(deserialize_with = "list")]
+    pub cross: Vec<String>,
+    /// Names of directories or full paths that are not scanned
+    #[serde(deserialize_with = "list")]
+    pub exclude: Vec<String>,
+    /// Whether other filesystems are skipped
+    pub one_file_system: Option<bool>,
+    /// Interval of polling watcher, native watcher is used if not set
+    #[serde(deserialize_with = "poll_interval")]
+    pub poll_interval: Option<Duration>,
+    /// Filesystem types that are treated as pseudo
+    #[serde(deserialize_with = "list")]
+    pub pseudo_fs: Vec<String>,
+    /// Filesystem types that are treated as real
+    #[serde(deserialize_with = "list")]
+    pub real_fs: Vec<String>,
+    #[serde(deserialize_with = "duration")]
+    pub rescan_delay: Option<Duration>,
+    pub skip_snapshots: Option<bool>,
+}
+
+impl ScannerConfig {
+    /// Parses config from TOML document where options are top level keys
+    pub fn parse(text: &str) -> Result<ScannerConfig, toml::de::Error> {
+        toml::from_str(text)
+    }
+}
+
+/// Single string that is accepted where list of strings is expected
+#[derive(Debug, Deserialize)]
+#[serde(untagged, expecting = "expected string or array of strings")]
+pub enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl From<OneOrMany> for Vec<String> {
+    fn from(value: OneOrMany) -> Self {
+        match value {
+            OneOrMany::One(s) => vec![s],
+            OneOrMany::Many(v) => v,
+        }
+    }
+}
+
+/// Returns error message if polling is so frequent that it would keep a core busy
+pub fn check_poll_interval(interval: Duration) -> Result<(), String> {
+    if interval < MIN_POLL_INTERVAL {
+        return Err(format!(
+            "poll interval must be at least {} ms",
+            MIN_POLL_INTERVAL.as_millis()
+        ));
+    }
+    Ok(())
+}
+
+/// Returns duration that is specified in milliseconds
+pub fn duration<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Duration>, D::Error> {
+    Ok(Some(Duration::from_millis(u64::deserialize(d)?)))
+}
+
+/// Returns list of strings, single string is a list with one element
+pub fn list<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<String>, D::Error> {
+    Ok(OneOrMany::deserialize(d)?.into())
+}
+
+/// Returns duration in milliseconds that passes [`check_poll_interval`]
+pub fn poll_interval<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Duration>, D::Error> {
+    let interval = duration(d)?;
+    if let Some(interval) = interval {
+        check_poll_interval(interval).map_err(de::Error::custom)?;
+    }
+    Ok(interval)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::ScannerConfig;
+
+    #[test]
+    fn parse() {
+        let config = ScannerConfig::parse(
+            r#"
+            one_file_system = true
+            cross = "/home"
+            real_fs = ["fuse.sshfs"]
+            poll_interval = 5000
+            rescan_delay = 300
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config,
+            ScannerConfig {
+                cross: vec!["/home".to_string()],
+                one_file_system: Some(true),
+                poll_interval: Some(Duration::from_secs(5)),
+                real_fs: vec!["fuse.sshfs".to_string()],
+                rescan_delay: Some(Duration::from_millis(300)),
+                ..ScannerConfig::default()
+            }
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = |text| ScannerConfig::parse(text).unwrap_err().to_string();
+        let unknown = error("exclude = []\nfoo = 1");
+        assert!(unknown.contains("line 2"), "{}", unknown);
+        assert!(unknown.contains("unknown field `foo`"), "{}", unknown);
+        assert!(error("exclude = 1").contains("string or array of strings"));
+        assert!(error("skip_snapshots = \"yes\"").contains("expected a boolean"));
+        assert!(error("rescan_delay = -5").contains("expected u64"));
+        assert!(error("poll_interval = 0").contains("at least 100 ms"));
+    }
+}
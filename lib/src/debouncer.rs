@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::path::EntryPath;
+
+/// Max number of times delay is doubled for directories that keep changing
+const MAX_BACKOFF: u32 = 5;
+
+#[derive(Debug)]
+struct Churn {
+    /// Delay before rescan is `window * 2^backoff`
+    backoff: u32,
+
+    /// When directory was rescanned last time
+    last_rescan: Instant,
+}
+
+/// Collects changed directories and decides when they should be rescanned
+///
+/// All changes of the same directory within window are collapsed into a single rescan.
+/// If directory changes again shortly after rescan, its delay is doubled
+/// (up to `2^MAX_BACKOFF` windows), so constantly changing directories
+/// (for example, with log files) don't keep scanner busy.
+#[derive(Debug)]
+pub struct Debouncer {
+    window: Duration,
+
+    /// Directories that should be rescanned and time when rescan is due
+    pending: HashMap<EntryPath, Instant>,
+
+    /// Recently rescanned directories
+    history: HashMap<EntryPath, Churn>,
+}
+
+impl Debouncer {
+    pub fn new(window: Duration) -> Self {
+        Debouncer {
+            window,
+            pending: HashMap::new(),
+            history: HashMap::new(),
+        }
+    }
+
+    /// Returns whether there are any directories waiting for rescan
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Removes all directories that should be rescanned by now and returns them
+    pub fn pop_ready(&mut self, now: Instant) -> Vec<EntryPath> {
+        let mut ready = vec![];
+        self.pending.retain(|path, due| {
+            if *due <= now {
+                ready.push(path.clone());
+                false
+            } else {
+                true
+            }
+        });
+
+        for path in &ready {
+            let window = self.window;
+            self.history
+                .entry(path.clone())
+                .and_modify(|churn| {
+                    // directory changed again before doubled delay passed, so increase it
+                    if now.duration_since(churn.last_rescan) < window * 2u32.pow(churn.backoff + 1)
+                    {
+                        churn.backoff = MAX_BACKOFF.min(churn.backoff + 1);
+                    } else {
+                        churn.backoff = 0;
+                    }
+                    churn.last_rescan = now;
+                })
+                .or_insert(Churn {
+                    backoff: 0,
+                    last_rescan: now,
+                });
+        }
+
+        // forget directories that were quiet long enough
+        let max_delay = self.window * 2u32.pow(MAX_BACKOFF + 1);
+        self.history
+            .retain(|_, churn| now.duration_since(churn.last_rescan) < max_delay);
+
+        ready
+    }
+
+    /// Adds changed directory
+    ///
+    /// Returns `false` if directory is already waiting for rescan
+    pub fn push(&mut self, path: EntryPath, now: Instant) -> bool {
+        if self.pending.contains_key(&path) {
+            return false;
+        }
+        let backoff = self.history.get(&path).map(|c| c.backoff).unwrap_or(0);
+        self.pending
+            .insert(path, now + self.window * 2u32.pow(backoff));
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use crate::debouncer::Debouncer;
+    use crate::path::EntryPath;
+
+    fn path(name: &str) -> EntryPath {
+        let mut path = EntryPath::new("/data".to_string());
+        path.join(name.to_string());
+        path
+    }
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn collapse_changes() {
+        let mut debouncer = Debouncer::new(ms(100));
+        let start = Instant::now();
+
+        assert!(debouncer.push(path("dir1"), start));
+        assert!(!debouncer.push(path("dir1"), start + ms(50)));
+        assert!(debouncer.push(path("dir2"), start + ms(50)));
+        assert!(debouncer.pop_ready(start + ms(99)).is_empty());
+
+        assert_eq!(debouncer.pop_ready(start + ms(100)), vec![path("dir1")]);
+        assert_eq!(debouncer.pop_ready(start + ms(150)), vec![path("dir2")]);
+        assert!(debouncer.is_empty());
+    }
+
+    #[test]
+    fn backoff() {
+        let mut debouncer = Debouncer::new(ms(100));
+        let mut now = Instant::now();
+
+        // directory keeps changing, so each next rescan is delayed twice as long
+        for delay in [100, 100, 200, 400, 800, 1600, 3200, 3200] {
+            debouncer.push(path("dir"), now);
+            assert!(debouncer.pop_ready(now + ms(delay - 1)).is_empty());
+            now += ms(delay);
+            assert_eq!(debouncer.pop_ready(now), vec![path("dir")]);
+        }
+
+        // after long enough pause, delay is reset
+        now += ms(10000);
+        debouncer.pop_ready(now);
+        debouncer.push(path("dir"), now);
+        assert_eq!(debouncer.pop_ready(now + ms(100)), vec![path("dir")]);
+    }
+}
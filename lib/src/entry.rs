@@ -1,11 +1,61 @@
 use std::cmp::Ordering;
-use std::fmt::Debug;
+use std::fmt::{Debug, Display, Formatter};
+use std::io::ErrorKind;
 
 use ptree::TreeBuilder;
 
 use crate::arena::{Arena, Id};
 use crate::path::{EntryPath, PathCrc};
 
+/// Reason why directory could not be scanned
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ScanError {
+    PermissionDenied,
+    NotFound,
+    Io,
+}
+
+impl From<ErrorKind> for ScanError {
+    fn from(kind: ErrorKind) -> Self {
+        match kind {
+            ErrorKind::PermissionDenied => ScanError::PermissionDenied,
+            ErrorKind::NotFound => ScanError::NotFound,
+            _ => ScanError::Io,
+        }
+    }
+}
+
+impl Display for ScanError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            ScanError::PermissionDenied => "Permission denied",
+            ScanError::NotFound => "Not found",
+            ScanError::Io => "I/O error",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// Filesystem mounted at directory that is located on another device than scanned path
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MountPoint {
+    /// Type of mounted filesystem (e.g. `ext4` or `nfs`), empty if unknown
+    pub fs_type: String,
+
+    /// Whether scanner crossed into this filesystem or skipped it
+    pub crossed: bool,
+}
+
+/// Btrfs subvolume located at directory
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Subvolume {
+    /// Whether subvolume is a snapshot of another subvolume
+    pub is_snapshot: bool,
+
+    /// Whether scanner skipped contents of this subvolume
+    pub skipped: bool,
+}
+
 /// Represents a directory in a directory tree
 ///
 /// Children of [`DirEntry`] are always sorted by size in descending order
@@ -33,6 +83,23 @@ pub struct DirEntry {
 
     /// Whether directory currently marked or not for bulk operations
     is_marked: bool,
+
+    /// Whether watcher failed to track changes inside this directory
+    is_unwatched: bool,
+
+    /// Whether scan was cancelled before this directory was read
+    is_incomplete: bool,
+
+    /// Error that happened during last scan of this directory
+    error: Option<ScanError>,
+
+    /// Set if another filesystem is mounted at this directory
+    ///
+    /// Boxed since mount points are rare and most entries shouldn't pay for it
+    mount: Option<Box<MountPoint>>,
+
+    /// Set if this directory is a root of btrfs subvolume
+    subvolume: Option<Subvolume>,
 }
 
 impl DirEntry {
@@ -124,11 +191,31 @@ impl DirEntry {
         }
     }
 
+    /// Ids of all child directories
+    pub fn get_children(&self) -> &[Id] {
+        &self.directories
+    }
+
+    /// Error that happened during last scan of this directory
+    pub fn get_error(&self) -> Option<ScanError> {
+        self.error
+    }
+
     /// Get number of files inside this directory
     pub fn get_files(&self) -> u32 {
         self.files
     }
 
+    /// Filesystem mounted at this directory, if it is located on another device
+    pub fn get_mount(&self) -> Option<&MountPoint> {
+        self.mount.as_deref()
+    }
+
+    /// Btrfs subvolume that has its root at this directory
+    pub fn get_subvolume(&self) -> Option<&Subvolume> {
+        self.subvolume.as_ref()
+    }
+
     /// Name of the entry
     pub fn get_name(&self) -> &str {
         &self.name
@@ -157,6 +244,16 @@ impl DirEntry {
         self.size
     }
 
+    /// Whether scan was cancelled before this directory was read
+    pub fn is_incomplete(&self) -> bool {
+        self.is_incomplete
+    }
+
+    /// Whether changes inside this directory are not tracked by watcher
+    pub fn is_unwatched(&self) -> bool {
+        self.is_unwatched
+    }
+
     /// Returns an iterator over child entries
     ///
     /// Entries are returned in size descending order. If entries have equal size
@@ -193,9 +290,19 @@ impl DirEntry {
             directories: vec![],
             files: 0,
             is_marked: false,
+            is_unwatched: false,
+            is_incomplete: false,
+            error: None,
+            mount: None,
+            subvolume: None,
         }
     }
 
+    /// Set whether scan was cancelled before this directory was read
+    pub fn set_incomplete(&mut self, incomplete: bool) {
+        self.is_incomplete = incomplete
+    }
+
     /// Called to indicate that size of some child changed and it should
     /// be reordered
     ///
@@ -289,6 +396,31 @@ impl DirEntry {
         let _ = ptree::print_tree(&tree);
     }
 
+    /// Removes entry with id `child_id` from children of entry with id `entry_id`
+    ///
+    /// Removed child is detached from tree so it can be renamed and added to
+    /// another entry. Path crc of its children is not updated.
+    ///
+    /// # Panics:
+    ///
+    /// Panics when child is not present in entry's children
+    pub fn remove_child(arena: &mut Arena<DirEntry>, entry_id: Id, child_id: Id) {
+        let entry = arena.get(entry_id);
+        let child = arena.get(child_id);
+        let idx = Self::find_child(&entry.directories, arena, &child.name, child.size)
+            .expect("Entry is not present in children");
+        assert_eq!(entry.directories[idx], child_id);
+        let path_crc = entry.path_crc;
+        let new_size = entry.size - child.size;
+
+        arena.get_mut(entry_id).directories.remove(idx);
+        let child = arena.get_mut(child_id);
+        child.parent = None;
+        // child doesn't have parent anymore so its path_crc is just name crc
+        child.path_crc ^= path_crc;
+        Self::set_size(arena, entry_id, new_size);
+    }
+
     /// Removes all marked children and returns them
     ///
     /// Returned ids are not removed from arena so cleanup is required
@@ -322,11 +454,32 @@ impl DirEntry {
         removed
     }
 
+    /// Changes name of entry that doesn't have a parent
+    ///
+    /// # Panics:
+    ///
+    /// Panics when entry has a parent
+    pub fn rename(&mut self, name: String) {
+        assert!(self.parent.is_none(), "Entry has a parent");
+        self.path_crc = EntryPath::calc_crc(&[&name]).unwrap();
+        self.name = name;
+    }
+
+    /// Set error that happened during last scan of this directory
+    pub fn set_error(&mut self, error: Option<ScanError>) {
+        self.error = error
+    }
+
     /// Set number of files inside this directory
     pub fn set_files(&mut self, files: u32) {
         self.files = files
     }
 
+    /// Set filesystem mounted at this directory
+    pub fn set_mount(&mut self, mount: Option<MountPoint>) {
+        self.mount = mount.map(Box::new)
+    }
+
     /// Set new size (size of all directories and files) of given directory
     pub fn set_size(arena: &mut Arena<DirEntry>, entry_id: Id, new_size: i64) {
         let entry = arena.get_mut(entry_id);
@@ -341,6 +494,23 @@ impl DirEntry {
         }
     }
 
+    /// Set btrfs subvolume that has its root at this directory
+    pub fn set_subvolume(&mut self, subvolume: Option<Subvolume>) {
+        self.subvolume = subvolume
+    }
+
+    /// Set whether changes inside this directory are not tracked by watcher
+    pub fn set_unwatched(&mut self, unwatched: bool) {
+        self.is_unwatched = unwatched
+    }
+
+    /// Applies change to crc of path (by XORing it with given `delta`)
+    ///
+    /// Used when one of the parents of this entry is renamed or moved
+    pub fn shift_path_crc(&mut self, delta: PathCrc) {
+        self.path_crc ^= delta;
+    }
+
     /// Removes children vec from directory
     ///
     /// Upon calling this function, directory should be already removed
@@ -535,6 +705,44 @@ mod tests {
         assert_eq!(root, &vec![dir3, dir1]);
     }
 
+    #[test]
+    fn remove_child_and_rename() {
+        let mut arena = Arena::default();
+
+        let root = new_dir(&mut arena, "root");
+        let dir1 = new_dir(&mut arena, "dir1");
+        let dir2 = new_dir(&mut arena, "dir2");
+        let dir11 = new_sized_dir(&mut arena, "dir11", 15);
+        let dir12 = new_sized_dir(&mut arena, "dir12", 25);
+        DirEntry::add_child(&mut arena, root, dir1);
+        DirEntry::add_child(&mut arena, root, dir2);
+        DirEntry::add_child(&mut arena, dir1, dir11);
+        DirEntry::add_child(&mut arena, dir1, dir12);
+        arena.get(root).print(&arena, 5);
+
+        DirEntry::remove_child(&mut arena, dir1, dir12);
+        assert_eq!(arena.get(dir1).size, 15);
+        assert_eq!(arena.get(root).size, 15);
+        assert_eq!(arena.get(dir12).parent, None);
+        assert_eq!(
+            arena.get(dir12).path_crc,
+            EntryPath::calc_crc(&["dir12"]).unwrap()
+        );
+
+        arena.get_mut(dir12).rename("dir21".to_string());
+        DirEntry::add_child(&mut arena, dir2, dir12);
+        arena.get(root).print(&arena, 5);
+        assert_eq!(arena.get(dir2).size, 25);
+        assert_eq!(arena.get(root).size, 40);
+        assert_eq!(&arena.get(root).directories, &vec![dir2, dir1]);
+
+        let mut path = EntryPath::new("root".to_string());
+        path.join("dir2".to_string());
+        path.join("dir21".to_string());
+        assert!(arena.get(dir12).compare_path(&arena, &path));
+        assert_eq!(arena.get(dir12).path_crc, path.get_crc());
+    }
+
     #[test]
     fn child_size_changed() {
         let mut arena = Arena::default();
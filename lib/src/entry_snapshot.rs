@@ -3,6 +3,7 @@ use std::ops::Deref;
 use byte_unit::Byte;
 
 use crate::arena::{Arena, Id};
+use crate::entry::{MountPoint, ScanError, Subvolume};
 
 #[derive(Debug)]
 pub struct EntrySnapshot {
@@ -16,6 +17,22 @@ pub struct EntrySnapshot {
     parent: Option<Id>,
 
     children: Option<Vec<Id>>,
+
+    /// Whether changes inside this entry are not tracked by watcher,
+    /// set for whole subtree of unwatched directory
+    is_unwatched: bool,
+
+    /// Whether scan was cancelled before this entry was read
+    is_incomplete: bool,
+
+    /// Error that happened during last scan of this entry
+    error: Option<ScanError>,
+
+    /// Filesystem mounted at this entry
+    mount: Option<MountPoint>,
+
+    /// Btrfs subvolume that has its root at this entry
+    subvolume: Option<Subvolume>,
 }
 
 impl EntrySnapshot {
@@ -23,10 +40,18 @@ impl EntrySnapshot {
         self.children.as_ref().map(|s| s.len()).unwrap_or(0)
     }
 
+    pub fn get_error(&self) -> Option<ScanError> {
+        self.error
+    }
+
     pub fn get_id(&self) -> Id {
         self.id
     }
 
+    pub fn get_mount(&self) -> Option<&MountPoint> {
+        self.mount.as_ref()
+    }
+
     pub fn get_name(&self) -> &str {
         &self.name
     }
@@ -39,10 +64,22 @@ impl EntrySnapshot {
         self.size
     }
 
+    pub fn get_subvolume(&self) -> Option<&Subvolume> {
+        self.subvolume.as_ref()
+    }
+
     pub fn is_dir(&self) -> bool {
         self.children.is_some()
     }
 
+    pub fn is_incomplete(&self) -> bool {
+        self.is_incomplete
+    }
+
+    pub fn is_unwatched(&self) -> bool {
+        self.is_unwatched
+    }
+
     /// Returns new snapshot with given parameters
     ///
     /// Parent and children of snapshot are empty and should be set explicitly
@@ -54,6 +91,11 @@ impl EntrySnapshot {
             size: Byte::from_bytes(size as u64),
             parent: None,
             children: None,
+            is_unwatched: false,
+            is_incomplete: false,
+            error: None,
+            mount: None,
+            subvolume: None,
         }
     }
 
@@ -64,10 +106,35 @@ impl EntrySnapshot {
         self.children = Some(children);
     }
 
+    /// Sets error that happened during last scan of this entry
+    pub fn set_error(&mut self, error: Option<ScanError>) {
+        self.error = error;
+    }
+
+    /// Sets whether scan was cancelled before this entry was read
+    pub fn set_incomplete(&mut self, incomplete: bool) {
+        self.is_incomplete = incomplete;
+    }
+
+    /// Sets filesystem mounted at this entry
+    pub fn set_mount(&mut self, mount: Option<MountPoint>) {
+        self.mount = mount;
+    }
+
     /// Sets new parent of this snapshot
     pub fn set_parent(&mut self, id: Id) {
         self.parent = Some(id);
     }
+
+    /// Sets btrfs subvolume that has its root at this entry
+    pub fn set_subvolume(&mut self, subvolume: Option<Subvolume>) {
+        self.subvolume = subvolume;
+    }
+
+    /// Sets whether changes inside this entry are not tracked by watcher
+    pub fn set_unwatched(&mut self, unwatched: bool) {
+        self.is_unwatched = unwatched;
+    }
 }
 
 impl AsRef<EntrySnapshot> for EntrySnapshot {
@@ -101,25 +168,35 @@ impl<'a, W> Iterator for SnapshotRefIterator<'a, W> {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Debug)]
 pub struct EntrySnapshotRef<'a, W> {
     id: Id,
     arena: &'a Arena<W>,
 }
 
+// derive would require `W: Clone` though only reference to arena is copied
+impl<'a, W> Clone for EntrySnapshotRef<'a, W> {
+    fn clone(&self) -> Self {
+        EntrySnapshotRef {
+            id: self.id,
+            arena: self.arena,
+        }
+    }
+}
+
 impl<'a, W: AsRef<EntrySnapshot>> EntrySnapshotRef<'a, W> {
-    pub fn iter(&self) -> impl Iterator<Item = EntrySnapshotRef<'a, W>> {
-        self.arena
-            .get(self.id)
-            .as_ref()
-            .children
-            .as_ref()
-            .expect("iterate inside directory")
-            .iter()
-            .map(|&id| EntrySnapshotRef {
-                id,
-                arena: self.arena,
-            })
+    pub fn iter(&self) -> SnapshotRefIterator<'a, W> {
+        SnapshotRefIterator {
+            entries: self
+                .arena
+                .get(self.id)
+                .as_ref()
+                .children
+                .as_ref()
+                .expect("iterate inside directory")
+                .iter(),
+            arena: self.arena,
+        }
     }
 
     /// Returns reference to n-th child
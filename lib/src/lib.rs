@@ -3,17 +3,29 @@
 #[macro_use]
 extern crate log;
 
+#[cfg(feature = "config")]
+pub use config::ScannerConfig;
+pub use entry::{MountPoint, ScanError, Subvolume};
 pub use entry_snapshot::{EntrySnapshot, EntrySnapshotRef};
 pub use path::EntryPath;
-pub use platform::{delete_path, get_available_mounts};
-pub use scanner::{ScanStats, Scanner, ScannerBuilder};
-pub use tree_snapshot::{SnapshotConfig, TreeSnapshot};
+pub use platform::{
+    delete_path, get_available_mounts, get_deleted_files, get_mount_stats, get_mounts, DeletedFile,
+    FsOverrides, MountInfo, MountStats,
+};
+pub use scan_event::{ScanEvent, EVENT_QUEUE_LEN};
+pub use scanner::{MountPolicy, ScanStats, Scanner, ScannerBuilder, WatcherKind};
+pub use tree_snapshot::{SnapshotConfig, SortOrder, TreeSnapshot};
+pub use watcher::MIN_POLL_INTERVAL;
 
 mod arena;
+#[cfg(feature = "config")]
+pub mod config;
+mod debouncer;
 mod entry;
 mod entry_snapshot;
 mod path;
 mod platform;
+mod scan_event;
 mod scanner;
 mod tree;
 mod tree_snapshot;
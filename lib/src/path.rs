@@ -10,7 +10,7 @@ pub type PathCrc = u16;
 
 const CRC_BUILDER: crc::Crc<PathCrc> = crc::Crc::<PathCrc>::new(&CRC_16_ISO_IEC_14443_3_A);
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub struct EntryPath {
     parts: Vec<String>,
 }
@@ -1,42 +1,138 @@
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::fs::{File, Metadata};
+use std::os::unix::fs::MetadataExt;
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
 
 use byte_unit::Byte;
 
-//todo can add more supported fs
-const SUPPORTED_FS: &[&str] = &[
-    "exfat", "ext2", "ext3", "ext4", "vfat", "ntfs", "fuseblk", "tmpfs",
+use crate::platform::{DeletedFile, FsOverrides, MountInfo};
+
+/// Filesystems that don't require block device but still store user files
+const NODEV_REAL_FS: &[&str] = &[
+    "9p", "cifs", "nfs", "nfs4", "overlay", "smb3", "smbfs", "tmpfs", "virtiofs", "zfs",
 ];
 
+/// Suffix that kernel appends to path of open file after it is deleted
+const DELETED_SUFFIX: &str = " (deleted)";
+
+/// Inode number of root directory of every btrfs subvolume
+const BTRFS_SUBVOLUME_INO: u64 = 256;
+
+/// Layout of `struct btrfs_ioctl_get_subvol_info_args` from `linux/btrfs.h`
+#[repr(C)]
+struct SubvolInfo {
+    tree_id: u64,
+    name: [u8; 256],
+    parent_id: u64,
+    dir_id: u64,
+    generation: u64,
+    flags: u64,
+    uuid: [u8; 16],
+    parent_uuid: [u8; 16],
+    received_uuid: [u8; 16],
+    transids: [u64; 4],
+    /// Change, creation, send and receive times
+    times: [Timespec; 4],
+    reserved: [u64; 8],
+}
+
+/// Layout of `struct btrfs_ioctl_timespec`
+#[repr(C)]
+struct Timespec {
+    sec: u64,
+    nsec: u32,
+}
+
+// BTRFS_IOC_GET_SUBVOL_INFO, available since linux 4.18 and doesn't require root
+nix::ioctl_read!(btrfs_get_subvol_info, 0x94, 60, SubvolInfo);
+
 /// Returns all mount points that can be scanned
-pub fn get_available_mounts() -> Vec<String> {
-    let mut mounts: Vec<_> = proc_mounts::MountIter::new()
-        .unwrap()
-        .map(|mount| mount.unwrap())
-        .filter(|mount| SUPPORTED_FS.contains(&mount.fstype.as_str()))
-        .filter_map(|mount| mount.dest.to_str().map(|s| s.to_string()))
-        .collect();
-    mounts.sort();
+pub fn get_available_mounts(overrides: &FsOverrides) -> Vec<String> {
+    get_mounts(overrides)
+        .into_iter()
+        .filter(|mount| !mount.is_pseudo)
+        .map(|mount| mount.path)
+        .collect()
+}
 
-    mounts
+/// Returns all deleted files that are still open by some process
+/// and located on the same filesystem as given path
+///
+/// Only processes of current user are visible unless it is root.
+/// Files are sorted by size in descending order
+pub fn get_deleted_files<P: AsRef<Path>>(path: P) -> Vec<DeletedFile> {
+    let device = match std::fs::metadata(path) {
+        Ok(metadata) => metadata.dev(),
+        Err(_) => return vec![],
+    };
+    let processes = match procfs::process::all_processes() {
+        Ok(processes) => processes,
+        Err(_) => return vec![],
+    };
+
+    // the same file might be open by several processes or several times,
+    // so files are identified by inode
+    let mut seen = HashSet::new();
+    let mut files = vec![];
+    for process in processes.filter_map(|p| p.ok()) {
+        let fds = match process.fd() {
+            Ok(fds) => fds,
+            Err(_) => continue,
+        };
+        let mut name = None;
+        for fd in fds.filter_map(|fd| fd.ok()) {
+            let target = match &fd.target {
+                procfs::process::FDTarget::Path(path) => path,
+                _ => continue,
+            };
+            let path = match target.to_str().and_then(|p| p.strip_suffix(DELETED_SUFFIX)) {
+                Some(path) => path,
+                None => continue,
+            };
+            // metadata of link in /proc is metadata of deleted file itself,
+            // file without links can't be reached by any path
+            let fd_path = format!("/proc/{}/fd/{}", process.pid, fd.fd);
+            let metadata = match std::fs::metadata(fd_path) {
+                Ok(m) if m.dev() == device && m.is_file() && m.nlink() == 0 => m,
+                _ => continue,
+            };
+            if !seen.insert(metadata.ino()) {
+                continue;
+            }
+            let process_name = name.get_or_insert_with(|| {
+                process
+                    .stat()
+                    .map(|stat| stat.comm)
+                    .unwrap_or_else(|_| process.pid.to_string())
+            });
+            files.push(DeletedFile {
+                path: path.to_string(),
+                size: Byte::from_bytes(metadata.blocks() * 512),
+                pid: process.pid,
+                process: process_name.clone(),
+            });
+        }
+    }
+    files.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+    files
 }
 
-/// Returns all mount points in system
+/// Returns all mount points of pseudo filesystems
 ///
-/// Some of them might be supported for scanning but should be excluded when
-/// scanning another mount point
-pub fn get_excluded_paths() -> Vec<PathBuf> {
-    let mut mounts: Vec<_> = proc_mounts::MountIter::new()
-        .unwrap()
-        .map(|mount| mount.unwrap())
-        .filter(|mount| !SUPPORTED_FS.contains(&mount.fstype.as_str()))
-        .map(|mount| mount.dest)
+/// They should be excluded when scanning another mount point
+pub fn get_excluded_paths(overrides: &FsOverrides) -> Vec<PathBuf> {
+    let mounts: Vec<_> = get_mounts(overrides)
+        .into_iter()
+        .filter(|mount| mount.is_pseudo)
+        .map(|mount| PathBuf::from(mount.path))
         .collect();
-    mounts.sort();
 
-    let mut excluded = vec![];
+    let mut excluded: Vec<PathBuf> = vec![];
 
     // collect only non overlapping mounts so we have less items
-    // for example /dev/sda will not be added because /dev already skips /dev/sda
+    // for example /dev/pts will not be added because /dev already skips /dev/pts
+    // mounts are sorted, so parent is always checked before children
     for mount in mounts {
         if !excluded.iter().any(|p| mount.starts_with(p)) {
             excluded.push(mount);
@@ -46,8 +142,170 @@ pub fn get_excluded_paths() -> Vec<PathBuf> {
     excluded
 }
 
+/// Returns type of filesystem mounted exactly at given path
+pub fn get_fs_type(path: &Path) -> Option<String> {
+    // when several filesystems are mounted at the same path, last one is visible
+    proc_mounts::MountIter::new()
+        .ok()?
+        .filter_map(|mount| mount.ok())
+        .filter(|mount| mount.dest == path)
+        .last()
+        .map(|mount| mount.fstype)
+}
+
+/// Returns all mounted filesystems
+///
+/// Filesystems that don't store user files (like `proc` or `sysfs`) are reported as pseudo
+pub fn get_mounts(overrides: &FsOverrides) -> Vec<MountInfo> {
+    let nodev = get_nodev_filesystems();
+    let mut mounts: Vec<_> = proc_mounts::MountIter::new()
+        .into_iter()
+        .flatten()
+        .filter_map(|mount| mount.ok())
+        .filter_map(|mount| {
+            let device = mount.source.to_str()?.to_string();
+            Some(MountInfo {
+                path: mount.dest.to_str()?.to_string(),
+                is_pseudo: is_pseudo_fs(&mount.fstype, &device, nodev.as_ref(), overrides),
+                device,
+                is_read_only: mount.options.iter().any(|o| o == "ro"),
+                fs_type: mount.fstype,
+            })
+        })
+        .collect();
+    // when several filesystems are mounted at the same path, only last one is visible,
+    // so mounts are reversed to keep it after stable sort and dedup
+    mounts.reverse();
+    mounts.sort_by(|a, b| a.path.cmp(&b.path));
+    mounts.dedup_by(|a, b| a.path == b.path);
+
+    mounts
+}
+
+/// Checks whether directory is a root of btrfs subvolume
+///
+/// Returns `Some(true)` if subvolume is a snapshot of another subvolume,
+/// `Some(false)` for regular subvolume and `None` if directory is not a subvolume
+pub fn get_subvolume(path: &Path, metadata: &Metadata) -> Option<bool> {
+    // check inode first so other filesystems don't need additional syscalls
+    if metadata.ino() != BTRFS_SUBVOLUME_INO {
+        return None;
+    }
+    let statfs = nix::sys::statfs::statfs(path).ok()?;
+    if statfs.filesystem_type() != nix::sys::statfs::BTRFS_SUPER_MAGIC {
+        return None;
+    }
+
+    // snapshots are subvolumes that have parent, if info is not available
+    // (e.g. on old kernels), treat it as regular subvolume
+    let is_snapshot = File::open(path)
+        .ok()
+        .and_then(|dir| {
+            // SAFETY: struct contains only integers and byte arrays,
+            // so all zero bytes is a valid value
+            let mut info: SubvolInfo = unsafe { std::mem::zeroed() };
+            // SAFETY: dir is an open file descriptor and info is a valid buffer,
+            // its size matches kernel struct (checked in tests) and is encoded in request number
+            unsafe { btrfs_get_subvol_info(dir.as_raw_fd(), &mut info) }.ok()?;
+            Some(info.parent_uuid.iter().any(|&b| b != 0))
+        })
+        .unwrap_or(false);
+    Some(is_snapshot)
+}
+
 pub fn get_used_memory() -> Option<Byte> {
     let statm = procfs::process::Process::myself().ok()?.statm().ok()?;
     let bytes = statm.resident * (page_size::get() as u64);
     Some(Byte::from_bytes(bytes))
 }
+
+/// Returns maximum number of directories that can be watched for changes
+///
+/// Limit is shared between all processes of current user
+pub fn get_watch_limit() -> Option<u64> {
+    std::fs::read_to_string("/proc/sys/fs/inotify/max_user_watches")
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Returns filesystems that don't require block device (marked as `nodev` in `/proc/filesystems`)
+fn get_nodev_filesystems() -> Option<HashSet<String>> {
+    let filesystems = std::fs::read_to_string("/proc/filesystems").ok()?;
+    Some(
+        filesystems
+            .lines()
+            .filter_map(|line| line.strip_prefix("nodev"))
+            .map(|fs| fs.trim().to_string())
+            .collect(),
+    )
+}
+
+/// Checks whether filesystem is virtual and doesn't store user files
+///
+/// Filesystem is real if it requires block device. Filesystems without it
+/// are real only if they are mounted from device or network share
+/// (e.g. `/dev/sda1`, `host:/export` or `//host/share`) or known to store files.
+fn is_pseudo_fs(
+    fs_type: &str,
+    device: &str,
+    nodev: Option<&HashSet<String>>,
+    overrides: &FsOverrides,
+) -> bool {
+    if let Some(pseudo) = overrides.is_pseudo(fs_type) {
+        return pseudo;
+    }
+    let has_device = device.starts_with("/dev/")
+        || device.starts_with("//")
+        || device.contains(":/")
+        || NODEV_REAL_FS.contains(&fs_type);
+    match nodev {
+        Some(nodev) => nodev.contains(fs_type) && !has_device,
+        // without list of filesystems only mounts from devices are considered real
+        None => !has_device,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::platform::linux::{is_pseudo_fs, SubvolInfo};
+    use crate::platform::FsOverrides;
+
+    #[test]
+    fn classify_filesystems() {
+        let nodev: HashSet<_> = ["proc", "sysfs", "tmpfs", "overlay", "fuse", "nfs4"]
+            .into_iter()
+            .map(|s| s.to_string())
+            .collect();
+        let nodev = Some(&nodev);
+        let overrides = FsOverrides::default();
+
+        assert!(!is_pseudo_fs("ext4", "/dev/sda1", nodev, &overrides));
+        assert!(!is_pseudo_fs("btrfs", "/dev/sda2", nodev, &overrides));
+        assert!(!is_pseudo_fs("overlay", "overlay", nodev, &overrides));
+        assert!(!is_pseudo_fs("nfs4", "server:/export", nodev, &overrides));
+        assert!(!is_pseudo_fs("fuse", "user@host:/home", nodev, &overrides));
+        assert!(is_pseudo_fs("proc", "proc", nodev, &overrides));
+        assert!(is_pseudo_fs("sysfs", "sysfs", nodev, &overrides));
+
+        // unknown filesystem without list of nodev filesystems
+        assert!(!is_pseudo_fs("xfs", "/dev/sdb", None, &overrides));
+        assert!(is_pseudo_fs("proc", "proc", None, &overrides));
+
+        let overrides = FsOverrides {
+            real: vec!["proc".to_string()],
+            pseudo: vec!["overlay".to_string()],
+        };
+        assert!(!is_pseudo_fs("proc", "proc", nodev, &overrides));
+        assert!(is_pseudo_fs("overlay", "overlay", nodev, &overrides));
+    }
+
+    #[test]
+    fn subvol_info_layout() {
+        // size is encoded in ioctl request number, so it must match kernel struct
+        assert_eq!(std::mem::size_of::<SubvolInfo>(), 504);
+    }
+}
@@ -1,28 +1,83 @@
-use std::path::PathBuf;
+use std::fs::Metadata;
+use std::path::{Path, PathBuf};
 
 use byte_unit::Byte;
 
 use libproc::libproc::pid_rusage;
 use libproc::libproc::pid_rusage::{PIDRUsage, RUsageInfoV0};
 
+use crate::platform::{DeletedFile, FsOverrides, MountInfo};
+
+/// Filesystems that don't store files on disk
+const PSEUDO_FS: &[&str] = &["autofs", "devfs", "nullfs"];
+
 /// Returns all mount points that can be scanned
-pub fn get_available_mounts() -> Vec<String> {
-    mountpoints::mountpaths()
-        .unwrap()
+pub fn get_available_mounts(overrides: &FsOverrides) -> Vec<String> {
+    get_mounts(overrides)
         .into_iter()
-        .map(|p| p.to_str().unwrap().to_string())
+        .filter(|mount| !mount.is_pseudo)
+        .map(|mount| mount.path)
         .collect()
 }
 
+/// Returns all deleted files that are still open by some process
+///
+/// Not supported on this platform, so always returns empty list
+pub fn get_deleted_files<P: AsRef<Path>>(_path: P) -> Vec<DeletedFile> {
+    vec![]
+}
+
 /// Returns all mount points in system
 ///
 /// Some of them might be supported for scanning but should be excluded when
 /// scanning another mount point
-pub fn get_excluded_paths() -> Vec<PathBuf> {
+pub fn get_excluded_paths(_overrides: &FsOverrides) -> Vec<PathBuf> {
     mountpoints::mountpaths().unwrap()
 }
 
+/// Returns type of filesystem that contains given path
+pub fn get_fs_type(path: &Path) -> Option<String> {
+    let statfs = nix::sys::statfs::statfs(path).ok()?;
+    Some(statfs.filesystem_type_name().to_string())
+}
+
+/// Returns all mounted filesystems
+///
+/// Device of filesystem is not reported
+pub fn get_mounts(overrides: &FsOverrides) -> Vec<MountInfo> {
+    mountpoints::mountinfos()
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|info| {
+            let fs_type = info.format.unwrap_or_default();
+            Some(MountInfo {
+                path: info.path.to_str()?.to_string(),
+                is_pseudo: overrides
+                    .is_pseudo(&fs_type)
+                    .unwrap_or_else(|| PSEUDO_FS.contains(&fs_type.as_str())),
+                fs_type,
+                device: String::new(),
+                is_read_only: info.readonly.unwrap_or(false),
+            })
+        })
+        .collect()
+}
+
+/// Checks whether directory is a root of btrfs subvolume
+///
+/// Btrfs is not available on this platform, so always returns `None`
+pub fn get_subvolume(_path: &Path, _metadata: &Metadata) -> Option<bool> {
+    None
+}
+
 pub fn get_used_memory() -> Option<Byte> {
     let info: RUsageInfoV0 = pid_rusage::pidrusage(std::process::id() as i32).ok()?;
     Some(Byte::from_bytes(info.memory_used()))
 }
+
+/// Returns maximum number of directories that can be watched for changes
+///
+/// Whole tree is watched recursively, so there is no limit
+pub fn get_watch_limit() -> Option<u64> {
+    None
+}
@@ -20,6 +20,63 @@ mod unix;
 #[cfg(windows)]
 mod windows;
 
+/// File that was deleted but still occupies disk space because some process keeps it open
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DeletedFile {
+    /// Path of file before it was deleted
+    pub path: String,
+
+    /// Size of file on disk
+    pub size: Byte,
+
+    /// Id of process that keeps file open
+    pub pid: i32,
+
+    /// Name of process that keeps file open
+    pub process: String,
+}
+
+/// User defined classification of filesystem types that takes priority over detection
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FsOverrides {
+    /// Filesystem types that are always treated as real and can be scanned
+    pub real: Vec<String>,
+
+    /// Filesystem types that are always treated as pseudo (virtual)
+    pub pseudo: Vec<String>,
+}
+
+impl FsOverrides {
+    /// Returns whether filesystem type is pseudo or `None` if it is not overridden
+    pub fn is_pseudo(&self, fs_type: &str) -> Option<bool> {
+        if self.pseudo.iter().any(|t| t == fs_type) {
+            Some(true)
+        } else if self.real.iter().any(|t| t == fs_type) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+
+/// Information about mounted filesystem
+#[derive(Clone, Debug)]
+pub struct MountInfo {
+    /// Path where filesystem is mounted
+    pub path: String,
+
+    /// Type of filesystem (e.g. `ext4`), empty if unknown
+    pub fs_type: String,
+
+    /// Device (or other source) of filesystem, empty if unknown
+    pub device: String,
+
+    /// Whether filesystem is virtual (like `proc`) and doesn't store files on disk
+    pub is_pseudo: bool,
+
+    pub is_read_only: bool,
+}
+
 #[derive(Debug)]
 pub struct MountStats {
     /// Total size of partition
@@ -28,6 +85,9 @@ pub struct MountStats {
     /// Available space on partition
     pub available: Byte,
 
+    /// Free space on partition, including space reserved for privileged users
+    pub free: Byte,
+
     /// Whether info was requested for mount point (true)
     /// or for some directory inside mount point
     pub is_mount_point: bool,
@@ -6,6 +6,11 @@ use byte_unit::Byte;
 
 use crate::platform::MountStats;
 
+/// Returns id of device that contains file
+pub fn get_device_id(metadata: &Metadata) -> Option<u64> {
+    Some(metadata.dev())
+}
+
 /// Retrieve file size
 ///
 /// On unix return file size on disk since its fast
@@ -34,10 +39,13 @@ pub fn get_mount_stats<P: AsRef<Path>>(path: P) -> Option<MountStats> {
     let total = statvfs.fragment_size() * u64::from(statvfs.blocks());
     #[allow(clippy::useless_conversion)]
     let available = statvfs.fragment_size() * u64::from(statvfs.blocks_available());
+    #[allow(clippy::useless_conversion)]
+    let free = statvfs.fragment_size() * u64::from(statvfs.blocks_free());
 
     Some(MountStats {
         total: Byte::from_bytes(total),
         available: Byte::from_bytes(available),
+        free: Byte::from_bytes(free),
         is_mount_point,
     })
 }
@@ -10,10 +10,29 @@ use windows_sys::Win32::Storage::FileSystem;
 use windows_sys::Win32::System::ProcessStatus::PROCESS_MEMORY_COUNTERS;
 use windows_sys::Win32::System::{ProcessStatus, WindowsProgramming};
 
-use crate::platform::MountStats;
+use crate::platform::{DeletedFile, FsOverrides, MountInfo, MountStats};
+
+/// Returns all mounted volumes (including folder mount points)
+///
+/// All volumes store files, so overrides are not used
+pub fn get_mounts(_overrides: &FsOverrides) -> Vec<MountInfo> {
+    mountpoints::mountinfos()
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|info| {
+            Some(MountInfo {
+                path: info.path.to_str()?.to_string(),
+                fs_type: info.format.unwrap_or_default(),
+                device: info.name.unwrap_or_default(),
+                is_pseudo: false,
+                is_read_only: info.readonly.unwrap_or(false),
+            })
+        })
+        .collect()
+}
 
 /// Returns all drives that can be scanned
-pub fn get_available_mounts() -> Vec<String> {
+pub fn get_available_mounts(_overrides: &FsOverrides) -> Vec<String> {
     // SAFETY: call is always safe
     let mut drive_mask = unsafe { FileSystem::GetLogicalDrives() };
 
@@ -43,7 +62,21 @@ pub fn get_available_mounts() -> Vec<String> {
     drives
 }
 
-pub fn get_excluded_paths() -> Vec<PathBuf> {
+/// Returns all deleted files that are still open by some process
+///
+/// Not supported on this platform, so always returns empty list
+pub fn get_deleted_files<P: AsRef<Path>>(_path: P) -> Vec<DeletedFile> {
+    vec![]
+}
+
+/// Returns id of device that contains file
+///
+/// Not supported on windows since drives are scanned separately
+pub fn get_device_id(_metadata: &Metadata) -> Option<u64> {
+    None
+}
+
+pub fn get_excluded_paths(_overrides: &FsOverrides) -> Vec<PathBuf> {
     vec![]
 }
 
@@ -87,6 +120,13 @@ pub fn get_file_size(metadata: &Metadata) -> u64 {
     }
 }
 
+/// Returns type of filesystem mounted exactly at given path
+///
+/// Not supported on windows, see [`get_device_id`]
+pub fn get_fs_type(_path: &Path) -> Option<String> {
+    None
+}
+
 pub fn get_long_path<T: AsRef<U16CStr>>(str: T) -> Option<U16CString> {
     let str = str.as_ref().as_ptr();
     // SAFETY: str is a valid wide string, this call will return required size of buffer
@@ -115,6 +155,7 @@ pub fn get_mount_stats<P: AsRef<Path>>(path: P) -> Option<MountStats> {
 
     let mut free_bytes = 0u64;
     let mut total_bytes = 0u64;
+    let mut total_free_bytes = 0u64;
     // SAFETY: path is a valid widechar str and is null terminated
     // pointers to output variables are valid u64 pointers
     let status = unsafe {
@@ -122,7 +163,7 @@ pub fn get_mount_stats<P: AsRef<Path>>(path: P) -> Option<MountStats> {
             path.as_ptr(),
             &mut free_bytes,
             &mut total_bytes,
-            std::ptr::null_mut(),
+            &mut total_free_bytes,
         )
     };
     if status == 0 {
@@ -131,11 +172,19 @@ pub fn get_mount_stats<P: AsRef<Path>>(path: P) -> Option<MountStats> {
         Some(MountStats {
             is_mount_point,
             available: Byte::from_bytes(free_bytes),
+            free: Byte::from_bytes(total_free_bytes),
             total: Byte::from_bytes(total_bytes),
         })
     }
 }
 
+/// Checks whether directory is a root of btrfs subvolume
+///
+/// Btrfs is not available on this platform, so always returns `None`
+pub fn get_subvolume(_path: &Path, _metadata: &Metadata) -> Option<bool> {
+    None
+}
+
 pub fn get_used_memory() -> Option<Byte> {
     // SAFETY: this call is always safe
     let handle = unsafe { windows_sys::Win32::System::Threading::GetCurrentProcess() };
@@ -159,3 +208,10 @@ pub fn get_used_memory() -> Option<Byte> {
         None
     }
 }
+
+/// Returns maximum number of directories that can be watched for changes
+///
+/// Whole tree is watched recursively, so there is no limit
+pub fn get_watch_limit() -> Option<u64> {
+    None
+}
@@ -0,0 +1,144 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{Receiver, SyncSender, TrySendError};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use byte_unit::Byte;
+
+use crate::EntryPath;
+
+/// Max number of events that wait in queue of a single subscriber
+///
+/// Subscriber whose queue is full is unsubscribed, so its receiver is disconnected
+/// after remaining events are received and memory is not wasted if it stopped reading.
+pub const EVENT_QUEUE_LEN: usize = 64 * 1024;
+
+/// Event emitted by [`Scanner`](crate::Scanner) to its subscribers
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScanEvent {
+    /// Scan of path (with all its subdirectories) started
+    Started(EntryPath),
+
+    /// All queued directories were scanned
+    Finished(Duration),
+
+    /// Scan was cancelled before all queued directories were scanned
+    ///
+    /// Directories that were not scanned are marked as incomplete
+    Cancelled(Duration),
+
+    /// Directory was read and its contents were updated in tree
+    DirScanned(EntryPath),
+
+    /// Size of directory changed after it was scanned
+    ///
+    /// Sizes of all its parents were changed by the same amount
+    SizeChanged {
+        path: EntryPath,
+        old_size: Byte,
+        new_size: Byte,
+    },
+
+    /// Directory could not be read
+    Error {
+        path: EntryPath,
+        kind: std::io::ErrorKind,
+    },
+
+    /// Watcher detected changes inside directory so it is queued for rescan
+    Changed(EntryPath),
+
+    /// Watcher detected that directory was moved, so it was moved inside tree too
+    Moved { from: EntryPath, to: EntryPath },
+}
+
+/// Delivers events to all subscribers
+#[derive(Debug, Default)]
+pub struct EventSender {
+    senders: Mutex<Vec<SyncSender<ScanEvent>>>,
+
+    /// Number of subscribers, used to avoid creating events when nobody listens
+    count: AtomicUsize,
+}
+
+impl EventSender {
+    pub fn has_subscribers(&self) -> bool {
+        self.count.load(Ordering::SeqCst) > 0
+    }
+
+    /// Sends event to all subscribers
+    ///
+    /// Event is created only if there are any subscribers.
+    /// Subscribers that dropped their receivers or have full queues are removed.
+    pub fn send<F: FnOnce() -> ScanEvent>(&self, event: F) {
+        if !self.has_subscribers() {
+            return;
+        }
+        let event = event();
+        let mut senders = self.senders.lock().unwrap();
+        senders.retain(|tx| match tx.try_send(event.clone()) {
+            Ok(()) => true,
+            Err(TrySendError::Full(_)) => {
+                warn!(
+                    "Subscriber didn't receive {} events, unsubscribing it",
+                    EVENT_QUEUE_LEN
+                );
+                false
+            }
+            Err(TrySendError::Disconnected(_)) => false,
+        });
+        self.count.store(senders.len(), Ordering::SeqCst);
+    }
+
+    pub fn subscribe(&self) -> Receiver<ScanEvent> {
+        let (tx, rx) = std::sync::mpsc::sync_channel(EVENT_QUEUE_LEN);
+        let mut senders = self.senders.lock().unwrap();
+        senders.push(tx);
+        self.count.store(senders.len(), Ordering::SeqCst);
+        rx
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::scan_event::{EventSender, ScanEvent, EVENT_QUEUE_LEN};
+
+    #[test]
+    fn send_to_subscribers() {
+        let sender = EventSender::default();
+        // nobody listens, so event is not even created
+        sender.send(|| unreachable!());
+
+        let rx1 = sender.subscribe();
+        let rx2 = sender.subscribe();
+        sender.send(|| ScanEvent::Finished(Duration::from_secs(1)));
+        assert_eq!(
+            rx1.try_recv().unwrap(),
+            ScanEvent::Finished(Duration::from_secs(1))
+        );
+        assert_eq!(
+            rx2.try_recv().unwrap(),
+            ScanEvent::Finished(Duration::from_secs(1))
+        );
+
+        drop(rx1);
+        drop(rx2);
+        sender.send(|| ScanEvent::Finished(Duration::from_secs(2)));
+        sender.send(|| unreachable!());
+    }
+
+    #[test]
+    fn unsubscribe_when_full() {
+        let sender = EventSender::default();
+        let rx = sender.subscribe();
+        for _ in 0..EVENT_QUEUE_LEN + 1 {
+            sender.send(|| ScanEvent::Finished(Duration::from_secs(1)));
+        }
+        assert!(!sender.has_subscribers());
+        assert_eq!(rx.try_iter().count(), EVENT_QUEUE_LEN);
+        assert!(rx.try_recv().is_err());
+    }
+}
@@ -1,6 +1,7 @@
 use std::collections::HashSet;
-use std::path::Path;
-use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::fs::Metadata;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
 use std::sync::mpsc::{Receiver, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread;
@@ -9,21 +10,45 @@ use std::time::{Duration, Instant};
 
 use byte_unit::Byte;
 
-use crate::entry::DirEntry;
+#[cfg(feature = "config")]
+use crate::config::ScannerConfig;
+use crate::debouncer::Debouncer;
+use crate::entry::{DirEntry, MountPoint, ScanError, Subvolume};
+use crate::scan_event::{EventSender, ScanEvent};
 use crate::tree::FileTree;
-use crate::watcher::Watcher;
-use crate::{platform, EntryPath, EntrySnapshot, SnapshotConfig, TreeSnapshot};
+use crate::watcher::{EventKind, FileEvent, PendingMoves, PollWatcher, Watcher, WatcherError};
+use crate::{platform, EntryPath, EntrySnapshot, FsOverrides, SnapshotConfig, TreeSnapshot};
 
 #[derive(Clone, Debug)]
 pub struct ScanStats {
     pub used_size: Byte,
     pub total_size: Option<Byte>,
     pub available_size: Option<Byte>,
+    /// Free space including blocks reserved for root
+    pub free_size: Option<Byte>,
     pub is_mount_point: bool,
     pub files: u64,
     pub dirs: u64,
     pub scan_duration: Duration,
     pub used_memory: Option<Byte>,
+    /// Number of directories where changes are not tracked
+    pub unwatched_dirs: u64,
+    /// Maximum number of directories that can be watched, if platform has such limit
+    pub watch_limit: Option<u64>,
+    /// Number of events received from watcher
+    pub watcher_events: u64,
+    /// Number of rescans caused by watcher events
+    pub watcher_rescans: u64,
+    /// Number of directories that could not be scanned
+    pub scan_errors: u64,
+    /// Number of directories that were not read because scan was cancelled
+    pub incomplete_dirs: u64,
+    /// Number of mount points that were not crossed
+    pub skipped_mounts: u64,
+    /// Number of btrfs snapshots that were not scanned
+    pub skipped_snapshots: u64,
+    /// Size of scanned directories located on other filesystems
+    pub crossed_size: Byte,
 }
 
 #[derive(Debug)]
@@ -32,11 +57,44 @@ struct ScanState {
 
     current_path: Mutex<Option<EntryPath>>,
 
+    /// Directories inside this path are scanned before all others
+    focus: Mutex<Option<EntryPath>>,
+
     is_scanning: AtomicBool,
 
     scan_flag: AtomicBool,
 
+    /// Whether processing of queued directories is paused
+    pause_flag: AtomicBool,
+
+    /// Set when all queued directories should be dropped
+    cancel_flag: AtomicBool,
+
     scan_duration_ms: AtomicU32,
+
+    watcher_events: AtomicU64,
+
+    watcher_rescans: AtomicU64,
+
+    events: EventSender,
+}
+
+impl ScanState {
+    fn new(tree: FileTree, events: EventSender) -> Self {
+        ScanState {
+            tree: Mutex::new(tree),
+            current_path: Mutex::new(None),
+            focus: Mutex::new(None),
+            is_scanning: AtomicBool::new(true),
+            scan_flag: AtomicBool::new(true),
+            pause_flag: AtomicBool::new(false),
+            cancel_flag: AtomicBool::new(false),
+            scan_duration_ms: AtomicU32::new(0),
+            watcher_events: AtomicU64::new(0),
+            watcher_rescans: AtomicU64::new(0),
+            events,
+        }
+    }
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -46,13 +104,165 @@ struct ScanTask {
     recursive: bool,
 }
 
+/// Defines how changes in file system are detected after directory is scanned
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum WatcherKind {
+    /// Use notifications provided by platform (inotify, FSEvents, etc.)
+    #[default]
+    Native,
+
+    /// Periodically check modification time of scanned directories
+    ///
+    /// Useful for filesystems that don't deliver notifications
+    /// (NFS, SMB, FUSE and others). Interval can't be shorter than [`MIN_POLL_INTERVAL`]
+    ///
+    /// [`MIN_POLL_INTERVAL`]: crate::MIN_POLL_INTERVAL
+    Polling(Duration),
+}
+
+/// Defines which mounted filesystems are scanned together with scanned path
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum MountPolicy {
+    /// Skip all mount points known at scan start and unsupported filesystems
+    #[default]
+    SkipKnown,
+
+    /// Stay on filesystem of scanned path (like `du -x`)
+    ///
+    /// Directories located on other devices are skipped, including
+    /// filesystems mounted after scan start
+    SameFilesystem,
+
+    /// Stay on filesystem of scanned path, but also scan filesystems mounted at listed paths
+    Cross(Vec<String>),
+}
+
 #[non_exhaustive]
-#[derive(Debug, Default)]
-pub struct ScannerBuilder;
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScannerBuilder {
+    watcher: WatcherKind,
+    rescan_delay: Duration,
+    mounts: MountPolicy,
+    fs_overrides: FsOverrides,
+    skip_snapshots: bool,
+    excludes: Vec<String>,
+}
+
+impl Default for ScannerBuilder {
+    fn default() -> Self {
+        ScannerBuilder {
+            watcher: WatcherKind::default(),
+            rescan_delay: Duration::from_millis(200),
+            mounts: MountPolicy::default(),
+            fs_overrides: FsOverrides::default(),
+            skip_snapshots: false,
+            excludes: vec![],
+        }
+    }
+}
 
 impl ScannerBuilder {
+    /// Applies options that are set in config, other options are kept
+    ///
+    /// Lists from config are added to lists of builder. Non empty `cross` selects
+    /// [`MountPolicy::Cross`] with all crossed paths, otherwise `one_file_system` selects
+    /// between [`MountPolicy::SameFilesystem`] and [`MountPolicy::SkipKnown`]
+    #[cfg(feature = "config")]
+    pub fn config(mut self, config: &ScannerConfig) -> Self {
+        if !config.cross.is_empty() {
+            let mut cross = match self.mounts {
+                MountPolicy::Cross(cross) => cross,
+                _ => vec![],
+            };
+            cross.extend(config.cross.iter().cloned());
+            self.mounts = MountPolicy::Cross(cross);
+        } else if let Some(one_file_system) = config.one_file_system {
+            self.mounts = if one_file_system {
+                MountPolicy::SameFilesystem
+            } else {
+                MountPolicy::SkipKnown
+            };
+        }
+        self.excludes.extend(config.exclude.iter().cloned());
+        self.fs_overrides
+            .pseudo
+            .extend(config.pseudo_fs.iter().cloned());
+        self.fs_overrides
+            .real
+            .extend(config.real_fs.iter().cloned());
+        if let Some(interval) = config.poll_interval {
+            self.watcher = WatcherKind::Polling(interval);
+        }
+        if let Some(delay) = config.rescan_delay {
+            self.rescan_delay = delay;
+        }
+        if let Some(skip) = config.skip_snapshots {
+            self.skip_snapshots = skip;
+        }
+        self
+    }
+
+    /// Sets directories that are not scanned
+    ///
+    /// Entries that contain path separator are full paths, others are names
+    /// of directories that are skipped at any depth (e.g. `.git`)
+    pub fn excludes(mut self, excludes: Vec<String>) -> Self {
+        self.excludes = excludes;
+        self
+    }
+
+    /// Sets filesystem types that are treated as real or pseudo regardless of detection
+    pub fn fs_overrides(mut self, overrides: FsOverrides) -> Self {
+        self.fs_overrides = overrides;
+        self
+    }
+
+    pub fn get_fs_overrides(&self) -> &FsOverrides {
+        &self.fs_overrides
+    }
+
+    /// Sets which mounted filesystems are scanned
+    pub fn mounts(mut self, mounts: MountPolicy) -> Self {
+        self.mounts = mounts;
+        self
+    }
+
+    /// Sets how long to wait before rescanning directory changed by watcher
+    ///
+    /// All changes of the same directory within this delay are collapsed into single rescan.
+    /// Delay is increased for directories that keep changing
+    pub fn rescan_delay(mut self, delay: Duration) -> Self {
+        self.rescan_delay = delay;
+        self
+    }
+
     pub fn scan(self, path: String) -> Scanner {
-        Scanner::new(path)
+        Scanner::new(path, self, EventSender::default())
+    }
+
+    /// Starts scan of given path and returns receiver of all its events
+    ///
+    /// Receiver is subscribed before scan starts, so unlike [`Scanner::subscribe`]
+    /// first events (like [`ScanEvent::Started`]) are not missed
+    pub fn scan_subscribed(self, path: String) -> (Scanner, Receiver<ScanEvent>) {
+        let events = EventSender::default();
+        let rx = events.subscribe();
+        (Scanner::new(path, self, events), rx)
+    }
+
+    /// Sets whether contents of btrfs snapshots are skipped
+    ///
+    /// Snapshots share data with their subvolumes, so scanning them
+    /// counts the same files several times
+    pub fn skip_snapshots(mut self, skip: bool) -> Self {
+        self.skip_snapshots = skip;
+        self
+    }
+
+    /// Sets how changes in file system are detected
+    pub fn watcher(mut self, watcher: WatcherKind) -> Self {
+        self.watcher = watcher;
+        self
     }
 }
 
@@ -76,6 +286,11 @@ impl Scanner {
         self.state.current_path.lock().unwrap().clone()
     }
 
+    /// Returns all directories that could not be scanned, sorted by path
+    pub fn get_errors(&self) -> Vec<(EntryPath, ScanError)> {
+        self.state.tree.lock().unwrap().get_errors()
+    }
+
     pub fn get_tree(
         &self,
         root: &EntryPath,
@@ -102,10 +317,38 @@ impl Scanner {
         )
     }
 
+    /// Returns whether directory with given path is in scanned tree
+    pub fn has_dir(&self, path: &EntryPath) -> bool {
+        self.state.tree.lock().unwrap().find_entry(path).is_some()
+    }
+
+    /// Stops current scan and keeps everything scanned so far
+    ///
+    /// All directories that were waiting for scan are marked as incomplete.
+    /// Paused scanner is resumed
+    pub fn cancel(&self) {
+        info!("Cancel scan");
+        self.state.cancel_flag.store(true, Ordering::SeqCst);
+        self.state.pause_flag.store(false, Ordering::SeqCst);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.state.pause_flag.load(Ordering::SeqCst)
+    }
+
     pub fn is_scanning(&self) -> bool {
         self.state.is_scanning.load(Ordering::SeqCst)
     }
 
+    /// Pauses scanning of queued directories until [`resume`](Scanner::resume) is called
+    ///
+    /// Directories changed in the meantime are still queued for rescan
+    pub fn pause(&self) {
+        if !self.state.pause_flag.swap(true, Ordering::SeqCst) {
+            info!("Pause scan");
+        }
+    }
+
     pub fn rescan_path(&self, path: EntryPath, reset_stopwatch: bool) {
         info!("Start rescan of '{}'", path);
         self.tx
@@ -117,23 +360,174 @@ impl Scanner {
             .unwrap();
     }
 
+    pub fn resume(&self) {
+        if self.state.pause_flag.swap(false, Ordering::SeqCst) {
+            info!("Resume scan");
+        }
+    }
+
+    /// Sets directory that should be scanned before all others
+    ///
+    /// Queued directories inside focused path are scanned first, so directory that
+    /// user is looking at is completed sooner. Pass `None` to scan in usual order
+    pub fn set_focus(&self, path: Option<EntryPath>) {
+        *self.state.focus.lock().unwrap() = path;
+    }
+
+    /// Subscribes to events of this scanner
+    ///
+    /// Only events that happen after subscription are delivered, so start of scan
+    /// is usually missed, use [`ScannerBuilder::scan_subscribed`] to receive all events.
+    /// Receiver can be dropped at any time to unsubscribe. Receiver is also
+    /// disconnected if it falls behind by [`EVENT_QUEUE_LEN`](crate::EVENT_QUEUE_LEN) events
+    pub fn subscribe(&self) -> Receiver<ScanEvent> {
+        self.state.events.subscribe()
+    }
+
     pub fn stats(&self) -> ScanStats {
         let scan_stats = self.state.tree.lock().unwrap().stats();
         let scan_duration =
             Duration::from_millis(self.state.scan_duration_ms.load(Ordering::SeqCst) as u64);
-        let (total, available, is_mount) = platform::get_mount_stats(self.root.get_path())
-            .map(|s| (Some(s.total), Some(s.available), s.is_mount_point))
-            .unwrap_or((None, None, false));
+        let mount_stats = platform::get_mount_stats(self.root.get_path());
+        let is_mount = mount_stats
+            .as_ref()
+            .map(|s| s.is_mount_point)
+            .unwrap_or(false);
         ScanStats {
             used_size: scan_stats.used_size,
-            total_size: total,
-            available_size: available,
+            total_size: mount_stats.as_ref().map(|s| s.total),
+            available_size: mount_stats.as_ref().map(|s| s.available),
+            free_size: mount_stats.as_ref().map(|s| s.free),
             is_mount_point: is_mount,
             files: scan_stats.files,
             dirs: scan_stats.dirs,
             scan_duration,
             used_memory: platform::get_used_memory(),
+            unwatched_dirs: scan_stats.unwatched_dirs,
+            watch_limit: platform::get_watch_limit(),
+            watcher_events: self.state.watcher_events.load(Ordering::SeqCst),
+            watcher_rescans: self.state.watcher_rescans.load(Ordering::SeqCst),
+            scan_errors: scan_stats.errors,
+            incomplete_dirs: scan_stats.incomplete_dirs,
+            skipped_mounts: scan_stats.skipped_mounts,
+            skipped_snapshots: scan_stats.skipped_snapshots,
+            crossed_size: scan_stats.crossed_size,
+        }
+    }
+
+    /// Moves directories inside tree according to matched move events
+    ///
+    /// So after rescan of old and new parents moved directories are kept
+    /// and not scanned again. Sources of moves without matching `MovedTo` event
+    /// are kept in `moved_from` for a short time, as it may come with next events.
+    fn apply_moves(
+        root: &str,
+        state: &ScanState,
+        moved_from: &mut PendingMoves<PathBuf>,
+        events: &[FileEvent],
+        now: Instant,
+    ) {
+        for event in events {
+            if let (EventKind::MovedFrom(cookie), Some(path)) = (event.kind, event.entry_path()) {
+                moved_from.insert(cookie, path, now);
+            }
+        }
+        if moved_from.is_empty() {
+            return;
+        }
+        let mut tree = state.tree.lock().unwrap();
+        for event in events {
+            if let EventKind::MovedTo(cookie) = event.kind {
+                let from = moved_from
+                    .take(cookie)
+                    .and_then(|path| EntryPath::from(root, path));
+                let to = event
+                    .entry_path()
+                    .and_then(|path| EntryPath::from(root, path));
+                if let (Some(from), Some(to)) = (from, to) {
+                    if tree.move_entry(&from, &to) {
+                        debug!("Moved '{}' to '{}'", from, to);
+                        state.events.send(|| ScanEvent::Moved { from, to });
+                    }
+                }
+            }
+        }
+        moved_from.pop_expired(now);
+    }
+
+    /// Drops all queued tasks and marks their directories as incomplete
+    fn cancel_queue(state: &ScanState, queue: &mut Vec<ScanTask>, duration: Duration) {
+        let was_scanning = state.is_scanning.swap(false, Ordering::SeqCst);
+        if !queue.is_empty() {
+            let mut tree = state.tree.lock().unwrap();
+            for task in queue.drain(..) {
+                tree.set_incomplete(&task.path);
+            }
+            warn!(
+                "Scan cancelled, {} directories were not scanned",
+                tree.stats().incomplete_dirs
+            );
+        }
+        state.current_path.lock().unwrap().take();
+        if was_scanning {
+            state.events.send(|| ScanEvent::Cancelled(duration));
+        }
+    }
+
+    /// Checks whether directory is located on another device than scanned path
+    ///
+    /// Returns mount point if it is. Device of crossed mount point is added to `devices`
+    fn detect_mount(
+        devices: &mut HashSet<u64>,
+        crossed: &HashSet<PathBuf>,
+        path: &EntryPath,
+        metadata: &Metadata,
+    ) -> Option<MountPoint> {
+        let device = platform::get_device_id(metadata)?;
+        if devices.contains(&device) {
+            return None;
+        }
+        let path = path.get_path();
+        let mount = MountPoint {
+            fs_type: platform::get_fs_type(&path).unwrap_or_default(),
+            crossed: crossed.contains(&path),
+        };
+        if mount.crossed {
+            info!(
+                "Crossing into {} mount at '{}'",
+                mount.fs_type,
+                path.display()
+            );
+            devices.insert(device);
+        } else {
+            info!("Skipping {} mount at '{}'", mount.fs_type, path.display());
+        }
+        Some(mount)
+    }
+
+    /// Checks whether directory is a root of btrfs subvolume
+    ///
+    /// Subvolumes have their own device id, so when mounts are detected by device,
+    /// subvolumes that are not mounted separately are added to scanned devices
+    fn detect_subvolume(
+        devices: Option<&mut HashSet<u64>>,
+        skip_snapshots: bool,
+        path: &Path,
+        metadata: &Metadata,
+    ) -> Option<Subvolume> {
+        let is_snapshot = platform::get_subvolume(path, metadata)?;
+        let subvolume = Subvolume {
+            is_snapshot,
+            skipped: is_snapshot && skip_snapshots,
+        };
+        if subvolume.skipped {
+            info!("Skipping btrfs snapshot at '{}'", path.display());
+        } else if let Some(devices) = devices {
+            if platform::get_fs_type(path).is_none() {
+                devices.extend(platform::get_device_id(metadata));
+            }
         }
+        Some(subvolume)
     }
 
     fn merge_to_queue(queue: &mut Vec<ScanTask>, task: ScanTask) {
@@ -167,7 +561,7 @@ impl Scanner {
         queue.push(task);
     }
 
-    fn new(path: String) -> Self {
+    fn new(path: String, builder: ScannerBuilder, events: EventSender) -> Self {
         let tree = FileTree::new(path.clone());
         let root = tree.get_root().get_path(tree.get_arena());
         let (tx, rx) = std::sync::mpsc::channel();
@@ -177,15 +571,9 @@ impl Scanner {
             recursive: true,
         })
         .unwrap();
-        let state = Arc::new(ScanState {
-            tree: Mutex::new(tree),
-            current_path: Mutex::new(None),
-            is_scanning: AtomicBool::new(true),
-            scan_flag: AtomicBool::new(true),
-            scan_duration_ms: AtomicU32::new(0),
-        });
+        let state = Arc::new(ScanState::new(tree, events));
 
-        let scan_handle = Scanner::start_scan(path, Arc::clone(&state), rx);
+        let scan_handle = Scanner::start_scan(path, builder, Arc::clone(&state), rx);
 
         Scanner {
             root,
@@ -195,6 +583,19 @@ impl Scanner {
         }
     }
 
+    fn report_watch_limit(path: &EntryPath) {
+        warn!(
+            "Watch limit reached at '{}', changes in some directories will not be tracked",
+            path
+        );
+        if let Some(limit) = platform::get_watch_limit() {
+            warn!(
+                "Increase fs.inotify.max_user_watches (currently {}) and start new scan to track all changes",
+                limit
+            );
+        }
+    }
+
     /// Retrieve list of all files and their sizes at specified path
     /// Files are not sorted in any way
     fn retrieve_files(path: &Path) -> Vec<(String, i64)> {
@@ -218,24 +619,92 @@ impl Scanner {
             .unwrap_or_default()
     }
 
-    fn start_scan(root: String, state: Arc<ScanState>, rx: Receiver<ScanTask>) -> JoinHandle<()> {
+    /// Removes from queue and returns latest task inside focused path
+    ///
+    /// Returns `None` if there are no such tasks
+    fn take_focused(queue: &mut Vec<ScanTask>, focus: &EntryPath) -> Option<ScanTask> {
+        // usually last task is already inside focus, so search is short
+        queue
+            .iter()
+            .rposition(|task| task.path >= *focus)
+            .map(|i| queue.remove(i))
+    }
+
+    fn start_scan(
+        root: String,
+        builder: ScannerBuilder,
+        state: Arc<ScanState>,
+        rx: Receiver<ScanTask>,
+    ) -> JoinHandle<()> {
         thread::spawn(move || {
-            let mut watcher = crate::watcher::new_watcher(root.clone());
+            let mut watcher: Option<Box<dyn Watcher>> = match builder.watcher {
+                WatcherKind::Native => crate::watcher::new_watcher(root.clone())
+                    .map(|w| Box::new(w) as Box<dyn Watcher>),
+                WatcherKind::Polling(interval) => Some(Box::new(PollWatcher::new(interval))),
+            };
+            if watcher.is_none() {
+                warn!("Unable to watch '{}', changes will not be tracked", root);
+            }
 
             let mut start = Instant::now();
+            let mut paused_at: Option<Instant> = None;
+            let mut focus: Option<EntryPath> = None;
+            // whether queue has no tasks inside focus, so it shouldn't be searched again
+            let mut focus_exhausted = false;
 
             let mut queue: Vec<ScanTask> = vec![];
             let mut children = vec![];
+            let mut limit_reported = false;
+            let mut debouncer = Debouncer::new(builder.rescan_delay);
+            let mut moved_from = PendingMoves::default();
+
+            // devices that can be scanned, if mounts are detected by device id
+            let mut devices: Option<HashSet<u64>> = match builder.mounts {
+                MountPolicy::SkipKnown => None,
+                _ => {
+                    let device = std::fs::metadata(&root)
+                        .ok()
+                        .and_then(|m| platform::get_device_id(&m));
+                    if device.is_none() {
+                        warn!("Unable to get device of '{}', skipping known mounts", root);
+                    }
+                    device.map(|d| HashSet::from([d]))
+                }
+            };
+            let crossed: HashSet<PathBuf> = match &builder.mounts {
+                // collecting components removes trailing separators
+                MountPolicy::Cross(paths) => paths
+                    .iter()
+                    .map(|p| Path::new(p).components().collect())
+                    .collect(),
+                _ => HashSet::new(),
+            };
 
-            let available: HashSet<_> = platform::get_available_mounts().into_iter().collect();
-            // excluded paths are all available mounts (excluding root scan path)
-            // and all unsupported mounts
-            let excluded: HashSet<_> = platform::get_excluded_paths()
-                .into_iter()
-                .filter_map(|p| p.to_str().map(|s| s.to_string()))
-                .chain(available.into_iter())
-                .filter(|p| p != &root)
-                .collect();
+            let mut excluded: HashSet<_> = if devices.is_some() {
+                HashSet::new()
+            } else {
+                let available = platform::get_available_mounts(&builder.fs_overrides);
+                // excluded paths are all available mounts (excluding root scan path)
+                // and all unsupported mounts
+                platform::get_excluded_paths(&builder.fs_overrides)
+                    .into_iter()
+                    .filter_map(|p| p.to_str().map(|s| s.to_string()))
+                    .chain(available)
+                    .filter(|p| p != &root)
+                    .collect()
+            };
+            let (excluded_paths, excluded_names): (Vec<_>, Vec<_>) = builder
+                .excludes
+                .iter()
+                .partition(|e| e.contains(std::path::is_separator));
+            // collecting components removes trailing separators
+            excluded.extend(excluded_paths.into_iter().filter_map(|p| {
+                let path: PathBuf = Path::new(p).components().collect();
+                path.to_str().map(|s| s.to_string())
+            }));
+
+            // mounts that are not crossed, kept apart from `excluded` as they can be unmounted
+            let mut skipped_mounts = HashSet::new();
 
             info!("Start scan of '{}'", root);
 
@@ -243,17 +712,32 @@ impl Scanner {
                 while state.scan_flag.load(Ordering::SeqCst) {
                     // check for events
                     if let Some(w) = &mut watcher {
-                        for task in w
-                            .read_events()
+                        let events = w.read_events();
+                        state
+                            .watcher_events
+                            .fetch_add(events.len() as u64, Ordering::SeqCst);
+                        let now = Instant::now();
+                        Scanner::apply_moves(&root, &state, &mut moved_from, &events, now);
+                        for path in events
                             .into_iter()
                             .filter_map(|e| EntryPath::from(&root, e.updated_path))
-                            .map(|path| ScanTask {
-                                recursive: false,
-                                reset_stopwatch: false,
-                                path,
-                            })
                         {
-                            Scanner::merge_to_queue(&mut queue, task);
+                            debouncer.push(path, now);
+                        }
+                    }
+                    if !debouncer.is_empty() {
+                        for path in debouncer.pop_ready(Instant::now()) {
+                            state.watcher_rescans.fetch_add(1, Ordering::SeqCst);
+                            focus_exhausted = false;
+                            state.events.send(|| ScanEvent::Changed(path.clone()));
+                            Scanner::merge_to_queue(
+                                &mut queue,
+                                ScanTask {
+                                    recursive: false,
+                                    reset_stopwatch: false,
+                                    path,
+                                },
+                            );
                         }
                     }
                     // add all tasks to queue
@@ -262,48 +746,122 @@ impl Scanner {
                             start = Instant::now();
                             state.is_scanning.store(true, Ordering::SeqCst);
                         }
+                        if task.reset_stopwatch {
+                            state.events.send(|| ScanEvent::Started(task.path.clone()));
+                        }
                         Scanner::merge_to_queue(&mut queue, task);
+                        focus_exhausted = false;
+                    }
+                    if state.pause_flag.load(Ordering::SeqCst) {
+                        paused_at.get_or_insert_with(Instant::now);
+                    } else if let Some(paused_at) = paused_at.take() {
+                        // time spent in pause is not included in scan duration
+                        start += paused_at.elapsed();
+                    }
+                    if state.cancel_flag.swap(false, Ordering::SeqCst) {
+                        Scanner::cancel_queue(&state, &mut queue, start.elapsed());
                     }
-                    if !queue.is_empty() {
+                    if !queue.is_empty() && paused_at.is_none() {
                         break;
                     }
                     thread::sleep(Duration::from_millis(10));
                 }
 
-                if let Some(task) = queue.pop() {
-                    let task_path = task.path.to_string();
-                    if excluded.contains(&task_path) {
-                        continue;
+                let new_focus = state.focus.lock().unwrap().clone();
+                if new_focus != focus {
+                    focus = new_focus;
+                    focus_exhausted = false;
+                }
+                let focused = match &focus {
+                    Some(focus) if !focus_exhausted => {
+                        let task = Scanner::take_focused(&mut queue, focus);
+                        focus_exhausted = task.is_none();
+                        task
                     }
-                    watcher.as_mut().map(|w| w.add_dir(task_path));
+                    _ => None,
+                };
+
+                // excluded task is dropped here so that scan is still finished
+                // if it was the last one in queue
+                let task = focused.or_else(|| queue.pop()).filter(|task| {
+                    let path = task.path.to_string();
+                    !excluded.contains(&path) && !skipped_mounts.contains(&path)
+                });
+                if let Some(task) = task {
+                    let task_path = task.path.to_string();
+                    let watched = watcher.as_mut().map(|w| match w.add_dir(task_path) {
+                        Ok(_) => true,
+                        Err(WatcherError::DirLimitReached) => {
+                            if !limit_reported {
+                                limit_reported = true;
+                                Scanner::report_watch_limit(&task.path);
+                            }
+                            false
+                        }
+                        Err(_) => false,
+                    });
                     state
                         .current_path
                         .lock()
                         .unwrap()
                         .replace(task.path.clone());
-                    let entries: Vec<_> = std::fs::read_dir(&task.path.get_path())
+                    let mut error = None;
+                    let entries: Vec<_> = std::fs::read_dir(task.path.get_path())
                         .and_then(|dir| dir.collect::<Result<_, _>>())
-                        .unwrap_or_else(|_| {
-                            warn!("Unable to scan '{}'", task.path);
+                        .unwrap_or_else(|e| {
+                            warn!("Unable to scan '{}': {}", task.path, e);
+                            error = Some(ScanError::from(e.kind()));
+                            state.events.send(|| ScanEvent::Error {
+                                path: task.path.clone(),
+                                kind: e.kind(),
+                            });
                             vec![]
                         });
 
                     let mut file_count = 0;
                     let mut files_size = 0;
+                    let mut mounts = vec![];
+                    let mut subvolumes = vec![];
                     for entry in entries {
                         if let Ok(metadata) = entry.metadata() {
                             let name = entry.file_name().to_str().unwrap().to_string();
-                            if task.recursive && metadata.is_dir() && !metadata.is_symlink() {
+                            if metadata.is_dir() && !metadata.is_symlink() {
                                 let mut path = task.path.clone();
                                 path.join(name.clone());
-                                queue.push(ScanTask {
-                                    path,
-                                    reset_stopwatch: false,
-                                    recursive: true,
+                                let subvolume = Scanner::detect_subvolume(
+                                    devices.as_mut(),
+                                    builder.skip_snapshots,
+                                    &entry.path(),
+                                    &metadata,
+                                );
+                                if let Some(subvolume) = subvolume {
+                                    if subvolume.skipped {
+                                        excluded.insert(path.to_string());
+                                    }
+                                    subvolumes.push((path.clone(), subvolume));
+                                }
+                                let mount = devices.as_mut().and_then(|devices| {
+                                    Scanner::detect_mount(devices, &crossed, &path, &metadata)
                                 });
-                            }
+                                // mount is recomputed on every rescan, so unmounted
+                                // directory is scanned again
+                                if mount.as_ref().is_some_and(|mount| !mount.crossed) {
+                                    skipped_mounts.insert(path.to_string());
+                                } else {
+                                    skipped_mounts.remove(&path.to_string());
+                                }
+                                mounts.push((path.clone(), mount));
+                                if excluded_names.contains(&&name) {
+                                    excluded.insert(path.to_string());
+                                }
+                                if task.recursive {
+                                    queue.push(ScanTask {
+                                        path,
+                                        reset_stopwatch: false,
+                                        recursive: true,
+                                    });
+                                }
 
-                            if metadata.is_dir() && !metadata.is_symlink() {
                                 // treat all directories as zero sized
                                 children.push(DirEntry::new_dir(name));
                             } else {
@@ -316,10 +874,43 @@ impl Scanner {
                     }
                     let new_dirs = {
                         let mut tree = state.tree.lock().unwrap();
-                        tree.set_children(&task.path, children, file_count, files_size)
+                        if let Some(watched) = watched {
+                            tree.set_watched(&task.path, watched);
+                        }
+                        tree.set_error(&task.path, error);
+                        let get_size = |tree: &FileTree| {
+                            tree.find_entry(&task.path)
+                                .map(|id| tree.get_arena().get(id).get_size())
+                        };
+                        let old_size = if state.events.has_subscribers() {
+                            get_size(&tree)
+                        } else {
+                            None
+                        };
+                        let new_dirs =
+                            tree.set_children(&task.path, children, file_count, files_size);
+                        for (path, mount) in mounts {
+                            tree.set_mount(&path, mount);
+                        }
+                        for (path, subvolume) in subvolumes {
+                            tree.set_subvolume(&path, subvolume);
+                        }
+                        if let (Some(old_size), Some(new_size)) = (old_size, get_size(&tree)) {
+                            if old_size != new_size {
+                                state.events.send(|| ScanEvent::SizeChanged {
+                                    path: task.path.clone(),
+                                    old_size: Byte::from_bytes(old_size as u64),
+                                    new_size: Byte::from_bytes(new_size as u64),
+                                });
+                            }
+                        }
+                        new_dirs
                     };
 
                     if let Some(new_dirs) = new_dirs {
+                        state
+                            .events
+                            .send(|| ScanEvent::DirScanned(task.path.clone()));
                         if !task.recursive {
                             for dir in new_dirs {
                                 let mut path = task.path.clone();
@@ -339,12 +930,12 @@ impl Scanner {
                     state.scan_duration_ms.store(duration, Ordering::SeqCst);
                     if queue.is_empty() {
                         let stats = state.tree.lock().unwrap().stats();
+                        let duration = Duration::from_millis(duration as u64);
                         info!(
                             "Scan finished: {} files {} dirs in {:?}",
-                            stats.files,
-                            stats.dirs,
-                            Duration::from_millis(duration as u64)
+                            stats.files, stats.dirs, duration
                         );
+                        state.events.send(|| ScanEvent::Finished(duration));
                     }
                 }
                 if queue.is_empty() {
@@ -362,3 +953,152 @@ impl Drop for Scanner {
         let _ = self.scan_handle.take().unwrap().join();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use crate::entry::DirEntry;
+    use crate::path::EntryPath;
+    use crate::scan_event::EventSender;
+    use crate::scanner::{ScanState, ScanTask, Scanner, ScannerBuilder};
+    use crate::tree::FileTree;
+    use crate::watcher::{EventKind, FileEvent, PendingMoves};
+    use crate::ScanEvent;
+
+    fn task(path: &str) -> ScanTask {
+        ScanTask {
+            path: EntryPath::from("/data", path).unwrap(),
+            reset_stopwatch: false,
+            recursive: true,
+        }
+    }
+
+    #[test]
+    fn move_split_between_reads() {
+        let mut tree = FileTree::new("/data".to_string());
+        let dirs = ["dir1", "dir2", "dir3"].map(|name| DirEntry::new_dir(name.to_string()));
+        tree.set_children(&EntryPath::new("/data".to_string()), dirs.into(), 0, 0);
+        let state = ScanState::new(tree, EventSender::default());
+        let mut moved_from = PendingMoves::default();
+        let event = |path: &str, name: &str, kind| FileEvent {
+            updated_path: path.to_string(),
+            name: Some(name.to_string()),
+            kind,
+        };
+        let has_dir = |path: &str| {
+            let path = EntryPath::from("/data", path).unwrap();
+            state.tree.lock().unwrap().find_entry(&path).is_some()
+        };
+        let start = Instant::now();
+
+        let first = [event("/data", "dir1", EventKind::MovedFrom(1))];
+        Scanner::apply_moves("/data", &state, &mut moved_from, &first, start);
+        assert!(has_dir("/data/dir1"));
+
+        let second = [event("/data/dir2", "moved", EventKind::MovedTo(1))];
+        Scanner::apply_moves("/data", &state, &mut moved_from, &second, start);
+        assert!(!has_dir("/data/dir1"));
+        assert!(has_dir("/data/dir2/moved"));
+
+        // unmatched move is forgotten after timeout
+        let third = [event("/data", "dir3", EventKind::MovedFrom(2))];
+        Scanner::apply_moves("/data", &state, &mut moved_from, &third, start);
+        let fourth = [event("/data/dir2", "late", EventKind::MovedTo(2))];
+        let late = start + Duration::from_secs(1);
+        Scanner::apply_moves("/data", &state, &mut moved_from, &[], late);
+        Scanner::apply_moves("/data", &state, &mut moved_from, &fourth, late);
+        assert!(has_dir("/data/dir3"));
+        assert!(!has_dir("/data/dir2/late"));
+    }
+
+    #[test]
+    fn subscribed_before_start() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("child")).unwrap();
+        let path = dir.path().to_str().unwrap().to_string();
+
+        let (scanner, rx) = ScannerBuilder::default().scan_subscribed(path.clone());
+        let first = rx.recv_timeout(Duration::from_secs(5));
+        let second = rx.recv_timeout(Duration::from_secs(5));
+        drop(scanner);
+
+        let root = EntryPath::new(path);
+        assert_eq!(first, Ok(ScanEvent::Started(root.clone())));
+        assert_eq!(second, Ok(ScanEvent::DirScanned(root)));
+    }
+
+    #[test]
+    fn take_focused() {
+        let mut queue = vec![
+            task("/data/dir1/dir2"),
+            task("/data/dir3"),
+            task("/data/dir1/dir4"),
+            task("/data/dir5"),
+        ];
+        let focus = EntryPath::from("/data", "/data/dir1").unwrap();
+
+        assert_eq!(
+            Scanner::take_focused(&mut queue, &focus),
+            Some(task("/data/dir1/dir4"))
+        );
+        assert_eq!(
+            Scanner::take_focused(&mut queue, &focus),
+            Some(task("/data/dir1/dir2"))
+        );
+        assert_eq!(Scanner::take_focused(&mut queue, &focus), None);
+        assert_eq!(queue, vec![task("/data/dir3"), task("/data/dir5")]);
+
+        // focus on directory itself
+        let focus = EntryPath::from("/data", "/data/dir3").unwrap();
+        assert_eq!(
+            Scanner::take_focused(&mut queue, &focus),
+            Some(task("/data/dir3"))
+        );
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn builder_config() {
+        use crate::scanner::{MountPolicy, WatcherKind};
+        use crate::ScannerConfig;
+
+        let builder = ScannerBuilder::default()
+            .excludes(vec![".git".to_string()])
+            .rescan_delay(Duration::from_secs(1));
+        let config = ScannerConfig {
+            exclude: vec!["/tmp".to_string()],
+            one_file_system: Some(true),
+            poll_interval: Some(Duration::from_secs(5)),
+            ..ScannerConfig::default()
+        };
+        let builder = builder.config(&config);
+        assert_eq!(builder.excludes, [".git", "/tmp"]);
+        assert_eq!(builder.mounts, MountPolicy::SameFilesystem);
+        assert_eq!(
+            builder.watcher,
+            WatcherKind::Polling(Duration::from_secs(5))
+        );
+        // options that are not set are kept
+        assert_eq!(builder.rescan_delay, Duration::from_secs(1));
+        assert!(!builder.skip_snapshots);
+
+        let builder = builder.config(&ScannerConfig {
+            one_file_system: Some(false),
+            skip_snapshots: Some(true),
+            ..ScannerConfig::default()
+        });
+        assert_eq!(builder.mounts, MountPolicy::SkipKnown);
+        assert!(builder.skip_snapshots);
+
+        let cross = |path: &str| ScannerConfig {
+            cross: vec![path.to_string()],
+            ..ScannerConfig::default()
+        };
+        let builder = builder.config(&cross("/home")).config(&cross("/mnt"));
+        assert_eq!(
+            builder.mounts,
+            MountPolicy::Cross(vec!["/home".to_string(), "/mnt".to_string()])
+        );
+    }
+}
@@ -1,9 +1,9 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use byte_unit::Byte;
 
 use crate::arena::{Arena, Id};
-use crate::entry::DirEntry;
+use crate::entry::{DirEntry, MountPoint, ScanError, Subvolume};
 use crate::path::{EntryPath, PathCrc};
 use crate::tree_snapshot::FilesRetrieverFn;
 use crate::{EntrySnapshot, SnapshotConfig, TreeSnapshot};
@@ -13,6 +13,15 @@ pub struct Stats {
     pub used_size: Byte,
     pub files: u64,
     pub dirs: u64,
+    pub unwatched_dirs: u64,
+    pub incomplete_dirs: u64,
+    pub errors: u64,
+    /// Number of mount points that were not crossed
+    pub skipped_mounts: u64,
+    /// Number of btrfs snapshots that were not scanned
+    pub skipped_snapshots: u64,
+    /// Size of scanned directories located on other filesystems
+    pub crossed_size: Byte,
 }
 
 #[derive(Debug)]
@@ -30,6 +39,18 @@ pub struct FileTree {
 
     files: u64,
     dirs: u64,
+
+    /// Number of directories where watcher failed to track changes
+    unwatched_dirs: u64,
+
+    /// Number of directories that were not read because scan was cancelled
+    incomplete_dirs: u64,
+
+    /// All directories that could not be scanned
+    errors: HashSet<Id>,
+
+    /// All directories that are mount points or roots of btrfs subvolumes
+    boundaries: HashSet<Id>,
 }
 
 impl FileTree {
@@ -68,6 +89,20 @@ impl FileTree {
         &self.arena
     }
 
+    /// Returns all directories that could not be scanned, sorted by path
+    pub fn get_errors(&self) -> Vec<(EntryPath, ScanError)> {
+        let mut errors: Vec<_> = self
+            .errors
+            .iter()
+            .map(|&id| {
+                let entry = self.arena.get(id);
+                (entry.get_path(&self.arena), entry.get_error().unwrap())
+            })
+            .collect();
+        errors.sort_by_cached_key(|(path, _)| path.to_string());
+        errors
+    }
+
     pub fn get_root(&self) -> &DirEntry {
         self.arena.get(self.root)
     }
@@ -101,6 +136,53 @@ impl FileTree {
         ))
     }
 
+    /// Moves directory with all its children to a new path
+    ///
+    /// New parent should already be present in tree and new path should be free.
+    /// Returns `false` if directory can't be moved, tree is not modified in this case
+    pub fn move_entry(&mut self, from: &EntryPath, to: &EntryPath) -> bool {
+        if to.is_root() || from.partial_cmp(to).is_some() {
+            // can't move root or move directory inside itself
+            return false;
+        }
+        let mut new_parent = to.clone();
+        new_parent.go_up();
+        let (Some(id), Some(new_parent), None) = (
+            self.find_entry(from),
+            self.find_entry(&new_parent),
+            self.find_entry(to),
+        ) else {
+            return false;
+        };
+        let old_parent = self.arena.get(id).get_parent().unwrap();
+
+        // path crc of all entries in subtree will change, so they are indexed again
+        let mut subtree = vec![id];
+        let mut i = 0;
+        while i < subtree.len() {
+            subtree.extend_from_slice(self.arena.get(subtree[i]).get_children());
+            i += 1;
+        }
+        for &id in &subtree {
+            self.remove_from_index(id);
+        }
+
+        let old_crc = self.arena.get(id).path_crc();
+        DirEntry::remove_child(&mut self.arena, old_parent, id);
+        self.arena.get_mut(id).rename(to.get_name().to_string());
+        DirEntry::add_child(&mut self.arena, new_parent, id);
+        let delta = old_crc ^ self.arena.get(id).path_crc();
+
+        for &child in &subtree[1..] {
+            self.arena.get_mut(child).shift_path_crc(delta);
+        }
+        for &id in &subtree {
+            self.add_to_index(id);
+        }
+
+        true
+    }
+
     /// Creates new [`FileTree`] rooted at specified path
     pub fn new(path: String) -> Self {
         let mut arena = Arena::default();
@@ -112,6 +194,10 @@ impl FileTree {
             entries: HashMap::new(),
             files: 0,
             dirs: 0,
+            unwatched_dirs: 0,
+            incomplete_dirs: 0,
+            errors: HashSet::new(),
+            boundaries: HashSet::new(),
         }
     }
 
@@ -132,6 +218,11 @@ impl FileTree {
         // and inserting them in bulk
         let mut new_dirs = vec![];
 
+        if self.arena.get(parent_id).is_incomplete() {
+            self.arena.get_mut(parent_id).set_incomplete(false);
+            self.incomplete_dirs -= 1;
+        }
+
         let (mut deleted_dirs, dirs_size) = DirEntry::mark_children(&mut self.arena, parent_id);
         // updated total file count
         self.files -= self.arena.get(parent_id).get_files() as u64;
@@ -159,14 +250,9 @@ impl FileTree {
             let child_id = self.arena.put(dir);
             DirEntry::add_child(&mut self.arena, parent_id, child_id);
 
-            let child = self.arena.get(child_id);
             self.dirs += 1;
-            new_dirs.push(child.get_name().to_string());
-            // store new entry in path crc map
-            self.entries
-                .entry(child.path_crc())
-                .or_default()
-                .push(child_id);
+            new_dirs.push(self.arena.get(child_id).get_name().to_string());
+            self.add_to_index(child_id);
         }
 
         if has_children {
@@ -177,33 +263,180 @@ impl FileTree {
         Some(new_dirs)
     }
 
+    /// Marks directory at specified path as not read because scan was cancelled
+    ///
+    /// Mark is cleared when children of directory are set.
+    /// Returns `false` if there is no such directory in tree
+    pub fn set_incomplete(&mut self, path: &EntryPath) -> bool {
+        if let Some(id) = self.find_entry(path) {
+            let entry = self.arena.get_mut(id);
+            if !entry.is_incomplete() {
+                entry.set_incomplete(true);
+                self.incomplete_dirs += 1;
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Sets error that happened during scan of directory at specified path
+    ///
+    /// Error is cleared if `None` is given. Directories not present in tree are ignored
+    pub fn set_error(&mut self, path: &EntryPath, error: Option<ScanError>) {
+        if error.is_none() && self.errors.is_empty() {
+            // nothing to clear, so skip search
+            return;
+        }
+        if let Some(id) = self.find_entry(path) {
+            self.arena.get_mut(id).set_error(error);
+            if error.is_some() {
+                self.errors.insert(id);
+            } else {
+                self.errors.remove(&id);
+            }
+        }
+    }
+
+    /// Sets filesystem mounted at directory at specified path
+    ///
+    /// `None` clears mount point, for example after filesystem was unmounted.
+    /// Returns `false` if there is no such directory in tree
+    pub fn set_mount(&mut self, path: &EntryPath, mount: Option<MountPoint>) -> bool {
+        if let Some(id) = self.find_entry(path) {
+            let entry = self.arena.get_mut(id);
+            entry.set_mount(mount);
+            if entry.get_mount().is_some() || entry.get_subvolume().is_some() {
+                self.boundaries.insert(id);
+            } else {
+                self.boundaries.remove(&id);
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Sets btrfs subvolume that has its root at specified path
+    ///
+    /// Returns `false` if there is no such directory in tree
+    pub fn set_subvolume(&mut self, path: &EntryPath, subvolume: Subvolume) -> bool {
+        if let Some(id) = self.find_entry(path) {
+            self.arena.get_mut(id).set_subvolume(Some(subvolume));
+            self.boundaries.insert(id);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Marks whether changes inside directory at specified path are tracked by watcher
+    ///
+    /// Only directory itself is marked, snapshots extend mark to its whole subtree.
+    /// Returns `false` if there is no such directory in tree
+    pub fn set_watched(&mut self, path: &EntryPath, watched: bool) -> bool {
+        if let Some(id) = self.find_entry(path) {
+            let entry = self.arena.get_mut(id);
+            if entry.is_unwatched() == watched {
+                entry.set_unwatched(!watched);
+                if watched {
+                    self.unwatched_dirs -= 1;
+                } else {
+                    self.unwatched_dirs += 1;
+                }
+            }
+            true
+        } else {
+            false
+        }
+    }
+
     /// Return size of tree (number of files and dirs)
     pub fn stats(&self) -> Stats {
+        let mut skipped_mounts = 0;
+        let mut skipped_snapshots = 0;
+        let mut crossed_size = 0;
+        for &id in &self.boundaries {
+            let entry = self.arena.get(id);
+            match entry.get_mount() {
+                // nested mounts are already included in size of their parent mount
+                Some(mount) if mount.crossed && !self.is_inside_crossed_mount(id) => {
+                    crossed_size += entry.get_size();
+                }
+                Some(mount) if !mount.crossed => skipped_mounts += 1,
+                _ => {}
+            }
+            if entry.get_subvolume().map(|s| s.skipped).unwrap_or(false) {
+                skipped_snapshots += 1;
+            }
+        }
         Stats {
+            skipped_mounts,
+            skipped_snapshots,
+            crossed_size: Byte::from_bytes(crossed_size as u64),
             files: self.files,
             dirs: self.dirs,
+            unwatched_dirs: self.unwatched_dirs,
+            incomplete_dirs: self.incomplete_dirs,
+            errors: self.errors.len() as u64,
             used_size: Byte::from_bytes(self.arena.get(self.root).get_size() as u64),
         }
     }
 
+    /// Stores entry in path crc map
+    fn add_to_index(&mut self, id: Id) {
+        self.entries
+            .entry(self.arena.get(id).path_crc())
+            .or_default()
+            .push(id);
+    }
+
     /// Cleans up removed ids recursively
     fn cleanup_removed(&mut self, entries: Vec<Id>) {
         self.dirs -= entries.len() as u64;
         for id in entries {
-            // remove entry from index
-            let path_crc = self.arena.get(id).path_crc();
-            let bin = self.entries.get_mut(&path_crc).unwrap();
-            if bin.len() == 1 {
-                self.entries.remove(&path_crc);
-            } else {
-                let pos = bin.iter().position(|&i| i == id).unwrap();
-                bin.swap_remove(pos);
-            }
+            self.remove_from_index(id);
 
-            let children = self.arena.remove(id).unwrap().take_children();
+            let entry = self.arena.remove(id).unwrap();
+            if entry.is_unwatched() {
+                self.unwatched_dirs -= 1;
+            }
+            if entry.is_incomplete() {
+                self.incomplete_dirs -= 1;
+            }
+            if entry.get_error().is_some() {
+                self.errors.remove(&id);
+            }
+            self.boundaries.remove(&id);
+            let children = entry.take_children();
             self.cleanup_removed(children);
         }
     }
+
+    /// Checks whether any parent of entry is a crossed mount point
+    fn is_inside_crossed_mount(&self, id: Id) -> bool {
+        let mut parent = self.arena.get(id).get_parent();
+        while let Some(id) = parent {
+            let entry = self.arena.get(id);
+            if entry.get_mount().map(|m| m.crossed).unwrap_or(false) {
+                return true;
+            }
+            parent = entry.get_parent();
+        }
+        false
+    }
+
+    /// Removes entry from path crc map
+    fn remove_from_index(&mut self, id: Id) {
+        let path_crc = self.arena.get(id).path_crc();
+        let bin = self.entries.get_mut(&path_crc).unwrap();
+        if bin.len() == 1 {
+            self.entries.remove(&path_crc);
+        } else {
+            let pos = bin.iter().position(|&i| i == id).unwrap();
+            bin.swap_remove(pos);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -212,11 +445,13 @@ mod tests {
     use std::fmt::Debug;
     use std::path::PathBuf;
 
-    use crate::entry::DirEntry;
+    use byte_unit::Byte;
+
+    use crate::entry::{DirEntry, MountPoint, ScanError, Subvolume};
     use crate::path::EntryPath;
     use crate::tree::FileTree;
     use crate::tree_snapshot::FilesRetrieverFn;
-    use crate::SnapshotConfig;
+    use crate::{SnapshotConfig, SortOrder};
 
     fn new_dir<T: Into<String>>(name: T) -> DirEntry {
         DirEntry::new_dir(name.into())
@@ -408,6 +643,273 @@ mod tests {
         );
     }
 
+    #[test]
+    fn move_entry() {
+        let root = "/data/mnt".to_string();
+        let mut tree = sample_tree();
+        tree.set_children(
+            &path(&root, "/data/mnt"),
+            vec![new_dir("dir1"), new_dir("dir3")],
+            2,
+            25,
+        );
+        tree.set_children(
+            &path(&root, "/data/mnt/dir1/dir2"),
+            vec![new_dir("dir4")],
+            3,
+            25,
+        );
+        tree.set_children(&path(&root, "/data/mnt/dir1/dir2/dir4"), vec![], 1, 10);
+        tree.get_root().print(tree.get_arena(), 5);
+
+        assert!(tree.move_entry(
+            &path(&root, "/data/mnt/dir1/dir2"),
+            &path(&root, "/data/mnt/dir3/dir5")
+        ));
+        tree.get_root().print(tree.get_arena(), 5);
+
+        assert_eq!(tree.find_entry(&path(&root, "/data/mnt/dir1/dir2")), None);
+        assert_eq!(
+            tree.find_entry(&path(&root, "/data/mnt/dir1/dir2/dir4")),
+            None
+        );
+        let dir5 = tree
+            .find_entry(&path(&root, "/data/mnt/dir3/dir5"))
+            .unwrap();
+        let dir4 = tree
+            .find_entry(&path(&root, "/data/mnt/dir3/dir5/dir4"))
+            .unwrap();
+        assert_eq!(tree.arena.get(dir5).get_size(), 35);
+        assert_eq!(tree.arena.get(dir4).get_size(), 10);
+        let dir1 = tree.find_entry(&path(&root, "/data/mnt/dir1")).unwrap();
+        let dir3 = tree.find_entry(&path(&root, "/data/mnt/dir3")).unwrap();
+        assert_eq!(tree.arena.get(dir1).get_size(), 25);
+        assert_eq!(tree.arena.get(dir3).get_size(), 35);
+
+        let stats = tree.stats();
+        assert_eq!(stats.used_size.get_bytes(), 85);
+        assert_eq!(stats.dirs, 4);
+
+        // existing paths, missing parents and moves inside itself are rejected
+        assert!(!tree.move_entry(
+            &path(&root, "/data/mnt/dir1"),
+            &path(&root, "/data/mnt/dir3")
+        ));
+        assert!(!tree.move_entry(
+            &path(&root, "/data/mnt/dir1"),
+            &path(&root, "/data/mnt/dir6/dir1")
+        ));
+        assert!(!tree.move_entry(
+            &path(&root, "/data/mnt/dir3"),
+            &path(&root, "/data/mnt/dir3/dir5/dir3")
+        ));
+
+        // rescan of new parent keeps moved subtree
+        let new_dirs = tree
+            .set_children(&path(&root, "/data/mnt/dir3"), vec![new_dir("dir5")], 0, 0)
+            .unwrap();
+        assert!(new_dirs.is_empty());
+        assert_eq!(tree.stats().used_size.get_bytes(), 85);
+    }
+
+    #[test]
+    fn set_error() {
+        let root = "/data/mnt".to_string();
+        let mut tree = sample_tree();
+
+        tree.set_error(
+            &path(&root, "/data/mnt/dir1/dir2"),
+            Some(ScanError::PermissionDenied),
+        );
+        tree.set_error(&path(&root, "/data/mnt"), Some(ScanError::Io));
+        tree.set_error(&path(&root, "/data/mnt/dir3"), Some(ScanError::Io));
+        assert_eq!(tree.stats().errors, 2);
+        assert_eq!(
+            tree.get_errors(),
+            vec![
+                (path(&root, "/data/mnt"), ScanError::Io),
+                (
+                    path(&root, "/data/mnt/dir1/dir2"),
+                    ScanError::PermissionDenied
+                ),
+            ]
+        );
+
+        let snapshot = tree
+            .make_snapshot(&root_path(&tree), SnapshotConfig::default(), &|_| vec![])
+            .unwrap();
+        let dir1 = snapshot.get_root().iter().next().unwrap();
+        assert_eq!(snapshot.get_root().get_error(), Some(ScanError::Io));
+        assert_eq!(dir1.get_error(), None);
+        assert_eq!(
+            dir1.iter().next().unwrap().get_error(),
+            Some(ScanError::PermissionDenied)
+        );
+
+        tree.set_error(&path(&root, "/data/mnt"), None);
+        assert_eq!(tree.stats().errors, 1);
+
+        // removed directories are not counted anymore
+        tree.set_children(&path(&root, "/data/mnt/dir1"), vec![], 1, 25);
+        assert_eq!(tree.stats().errors, 0);
+        assert!(tree.get_errors().is_empty());
+    }
+
+    #[test]
+    fn set_incomplete() {
+        let root = "/data/mnt".to_string();
+        let mut tree = sample_tree();
+
+        assert!(tree.set_incomplete(&path(&root, "/data/mnt/dir1/dir2")));
+        assert!(tree.set_incomplete(&path(&root, "/data/mnt/dir1/dir2")));
+        assert!(tree.set_incomplete(&path(&root, "/data/mnt/dir1")));
+        assert!(!tree.set_incomplete(&path(&root, "/data/mnt/dir3")));
+        assert_eq!(tree.stats().incomplete_dirs, 2);
+
+        let snapshot = tree
+            .make_snapshot(&root_path(&tree), SnapshotConfig::default(), &|_| vec![])
+            .unwrap();
+        let dir1 = snapshot.get_root().iter().next().unwrap();
+        assert!(!snapshot.get_root().is_incomplete());
+        assert!(dir1.is_incomplete());
+        assert!(dir1.iter().next().unwrap().is_incomplete());
+
+        // directory is complete again after it is scanned
+        tree.set_children(&path(&root, "/data/mnt/dir1/dir2"), vec![], 1, 25);
+        assert_eq!(tree.stats().incomplete_dirs, 1);
+
+        // removed directories are not counted anymore
+        tree.set_incomplete(&path(&root, "/data/mnt/dir1/dir2"));
+        tree.set_children(&root_path(&tree), vec![], 0, 0);
+        assert_eq!(tree.stats().incomplete_dirs, 0);
+    }
+
+    #[test]
+    fn set_mount() {
+        let root = "/data/mnt".to_string();
+        let mut tree = sample_tree();
+        let mount = MountPoint {
+            fs_type: "nfs".to_string(),
+            crossed: true,
+        };
+
+        assert!(tree.set_mount(&path(&root, "/data/mnt/dir1"), Some(mount.clone())));
+        assert!(!tree.set_mount(&path(&root, "/data/mnt/dir3"), Some(mount.clone())));
+
+        let snapshot = tree
+            .make_snapshot(&root_path(&tree), SnapshotConfig::default(), &|_| vec![])
+            .unwrap();
+        let dir1 = snapshot.get_root().iter().next().unwrap();
+        assert_eq!(snapshot.get_root().get_mount(), None);
+        assert_eq!(dir1.get_mount(), Some(&mount));
+        assert_eq!(dir1.iter().next().unwrap().get_mount(), None);
+
+        // unmounted filesystem is not a boundary anymore
+        assert!(tree.set_mount(&path(&root, "/data/mnt/dir1"), None));
+        let snapshot = tree
+            .make_snapshot(&root_path(&tree), SnapshotConfig::default(), &|_| vec![])
+            .unwrap();
+        assert_eq!(snapshot.get_root().iter().next().unwrap().get_mount(), None);
+        assert_eq!(tree.stats().crossed_size, Byte::from_bytes(0));
+    }
+
+    #[test]
+    fn boundary_stats() {
+        let root = "/data/mnt".to_string();
+        let mut tree = sample_tree();
+        let mount = |crossed| MountPoint {
+            fs_type: "nfs".to_string(),
+            crossed,
+        };
+        let children = vec![new_dir("dir1"), new_dir("dir4"), new_dir("dir5")];
+        tree.set_children(&root_path(&tree), children, 2, 25);
+
+        tree.set_mount(&path(&root, "/data/mnt/dir1"), Some(mount(true)));
+        tree.set_mount(&path(&root, "/data/mnt/dir1/dir2"), Some(mount(true)));
+        tree.set_mount(&path(&root, "/data/mnt/dir4"), Some(mount(false)));
+        tree.set_subvolume(
+            &path(&root, "/data/mnt/dir5"),
+            Subvolume {
+                is_snapshot: true,
+                skipped: true,
+            },
+        );
+        let stats = tree.stats();
+        assert_eq!(stats.skipped_mounts, 1);
+        assert_eq!(stats.skipped_snapshots, 1);
+        // dir2 is inside dir1, so it is not counted twice
+        assert_eq!(stats.crossed_size, Byte::from_bytes(50));
+
+        tree.set_children(&root_path(&tree), vec![], 0, 0);
+        let stats = tree.stats();
+        assert_eq!(stats.skipped_mounts, 0);
+        assert_eq!(stats.skipped_snapshots, 0);
+        assert_eq!(stats.crossed_size, Byte::from_bytes(0));
+    }
+
+    #[test]
+    fn set_subvolume() {
+        let root = "/data/mnt".to_string();
+        let mut tree = sample_tree();
+        let subvolume = Subvolume {
+            is_snapshot: true,
+            skipped: true,
+        };
+
+        assert!(tree.set_subvolume(&path(&root, "/data/mnt/dir1/dir2"), subvolume.clone()));
+        assert!(!tree.set_subvolume(&path(&root, "/data/mnt/dir3"), subvolume.clone()));
+
+        let snapshot = tree
+            .make_snapshot(&root_path(&tree), SnapshotConfig::default(), &|_| vec![])
+            .unwrap();
+        let dir1 = snapshot.get_root().iter().next().unwrap();
+        assert_eq!(dir1.get_subvolume(), None);
+        assert_eq!(
+            dir1.iter().next().unwrap().get_subvolume(),
+            Some(&subvolume)
+        );
+    }
+
+    #[test]
+    fn set_watched() {
+        let mut tree = sample_tree();
+
+        assert!(tree.set_watched(&path("/data/mnt", "/data/mnt/dir1"), false));
+        assert!(tree.set_watched(&path("/data/mnt", "/data/mnt/dir1/dir2"), false));
+        assert!(!tree.set_watched(&path("/data/mnt", "/data/mnt/dir3"), false));
+        assert_eq!(tree.stats().unwatched_dirs, 2);
+
+        // marking same directory again should not change anything
+        tree.set_watched(&path("/data/mnt", "/data/mnt/dir1/dir2"), false);
+        assert_eq!(tree.stats().unwatched_dirs, 2);
+
+        tree.set_watched(&path("/data/mnt", "/data/mnt/dir1/dir2"), true);
+        assert_eq!(tree.stats().unwatched_dirs, 1);
+
+        let snapshot = tree
+            .make_snapshot(&root_path(&tree), SnapshotConfig::default(), &|_| vec![])
+            .unwrap();
+        let dir1 = snapshot.get_root().iter().next().unwrap();
+        assert!(!snapshot.get_root().is_unwatched());
+        assert!(dir1.is_unwatched());
+        // mark is inherited by whole subtree of unwatched directory
+        assert!(dir1.iter().find(|e| e.is_dir()).unwrap().is_unwatched());
+
+        // also when snapshot starts inside of unwatched directory
+        let snapshot = tree
+            .make_snapshot(
+                &path("/data/mnt", "/data/mnt/dir1/dir2"),
+                SnapshotConfig::default(),
+                &|_| vec![],
+            )
+            .unwrap();
+        assert!(snapshot.get_root().is_unwatched());
+
+        // removed directories are not counted anymore
+        tree.set_children(&root_path(&tree), vec![], 2, 25);
+        assert_eq!(tree.stats().unwatched_dirs, 0);
+    }
+
     #[test]
     fn snapshot_from_root() {
         let tree = sample_tree();
@@ -530,6 +1032,33 @@ mod tests {
         assert!(dir1.iter().next().is_none());
     }
 
+    #[test]
+    fn snapshot_sorted_by_name() {
+        let tree = sample_tree();
+
+        let snapshot = tree
+            .make_snapshot(
+                &root_path(&tree),
+                SnapshotConfig {
+                    sort: SortOrder::Name,
+                    ..SnapshotConfig::default()
+                },
+                &sample_getter(),
+            )
+            .unwrap();
+
+        let root = snapshot.get_root();
+        let names: Vec<_> = root.iter().map(|e| e.get_name().to_string()).collect();
+        assert_eq!(names, ["dir1", "file1", "file2"]);
+        let dir1 = root.iter().next().unwrap();
+        let names: Vec<_> = dir1.iter().map(|e| e.get_name().to_string()).collect();
+        assert_eq!(names, ["dir2", "file3"]);
+        // smaller file is not moved to the end
+        let dir2 = dir1.iter().next().unwrap();
+        let names: Vec<_> = dir2.iter().map(|e| e.get_name().to_string()).collect();
+        assert_eq!(names, ["file4", "file5", "file6"]);
+    }
+
     #[test]
     fn snapshot_with_size_constraint() {
         let tree = sample_tree();
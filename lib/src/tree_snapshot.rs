@@ -12,11 +12,24 @@ use crate::EntrySnapshot;
 /// and their sizes at specified path
 pub type FilesRetrieverFn = dyn Fn(&Path) -> Vec<(String, i64)>;
 
+/// Order of children in snapshot
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SortOrder {
+    /// Largest entries first
+    #[default]
+    Size,
+
+    /// Alphabetical order of names
+    Name,
+}
+
 #[derive(Clone, Debug)]
 pub struct SnapshotConfig {
     pub max_depth: usize,
 
     pub min_size: u64,
+
+    pub sort: SortOrder,
 }
 
 impl Default for SnapshotConfig {
@@ -24,6 +37,7 @@ impl Default for SnapshotConfig {
         SnapshotConfig {
             max_depth: 3,
             min_size: 0,
+            sort: SortOrder::default(),
         }
     }
 }
@@ -61,6 +75,16 @@ impl<W: AsRef<EntrySnapshot> + AsMut<EntrySnapshot>> TreeSnapshot<W> {
             arena: snapshots,
         };
 
+        // changes inside whole subtree of unwatched directory are not tracked
+        let mut parent = entry.get_parent();
+        while let Some(id) = parent {
+            if arena.get(id).is_unwatched() {
+                tree.arena.get_mut(root).as_mut().set_unwatched(true);
+                break;
+            }
+            parent = arena.get(id).get_parent();
+        }
+
         tree.fill_snapshot(root, entry, arena, config, wrapper, files_getter);
 
         tree
@@ -125,6 +149,15 @@ impl<W: AsRef<EntrySnapshot> + AsMut<EntrySnapshot>> TreeSnapshot<W> {
         wrapper: &dyn Fn(EntrySnapshot) -> W,
         files_getter: &dyn Fn(&Path) -> Vec<(String, i64)>,
     ) {
+        let snapshot = self.arena.get_mut(id).as_mut();
+        // snapshot is already marked if any of its parents is unwatched
+        let unwatched = snapshot.is_unwatched() || entry.is_unwatched();
+        snapshot.set_unwatched(unwatched);
+        snapshot.set_incomplete(entry.is_incomplete());
+        snapshot.set_error(entry.get_error());
+        snapshot.set_mount(entry.get_mount().cloned());
+        snapshot.set_subvolume(entry.get_subvolume().cloned());
+
         if config.max_depth == 0 {
             self.arena.get_mut(id).as_mut().set_children(vec![]);
             return;
@@ -141,6 +174,7 @@ impl<W: AsRef<EntrySnapshot> + AsMut<EntrySnapshot>> TreeSnapshot<W> {
                         e.get_size(),
                     ))
                 });
+                self.arena.get_mut(id).as_mut().set_unwatched(unwatched);
 
                 self.fill_snapshot(
                     id,
@@ -180,9 +214,13 @@ impl<W: AsRef<EntrySnapshot> + AsMut<EntrySnapshot>> TreeSnapshot<W> {
             let a = self.arena.get(a).as_ref();
             let b = self.arena.get(b).as_ref();
 
-            b.get_size()
-                .cmp(&a.get_size())
-                .then_with(|| a.get_name().cmp(b.get_name()))
+            match config.sort {
+                SortOrder::Size => b
+                    .get_size()
+                    .cmp(&a.get_size())
+                    .then_with(|| a.get_name().cmp(b.get_name())),
+                SortOrder::Name => a.get_name().cmp(b.get_name()),
+            }
         });
 
         for &child in &children {
@@ -1,9 +1,11 @@
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 
 use inotify::{EventMask, Inotify, WatchDescriptor, WatchMask};
 use nix::libc::ENOSPC;
 
-use crate::watcher::{FileEvent, Watcher, WatcherError};
+use crate::watcher::{EventKind, FileEvent, PendingMoves, Watcher, WatcherError};
 
 const BUFFER_LEN: usize = 64 * 1024;
 
@@ -11,6 +13,9 @@ struct InotifyWatcher {
     inotify: Inotify,
     buffer: Vec<u8>,
     map: HashMap<WatchDescriptor, String>,
+
+    /// Directories that were moved out of some watched directory
+    moved_dirs: PendingMoves<PathBuf>,
 }
 
 pub fn new_watcher(root: String) -> Option<impl Watcher> {
@@ -21,6 +26,7 @@ pub fn new_watcher(root: String) -> Option<impl Watcher> {
         inotify,
         buffer,
         map: HashMap::new(),
+        moved_dirs: PendingMoves::default(),
     };
     watcher.add_dir(root).ok()?;
     Some(watcher)
@@ -50,30 +56,94 @@ impl Watcher for InotifyWatcher {
 
     fn read_events(&mut self) -> Vec<FileEvent> {
         let mut result = vec![];
-        let mm = EventMask::from_iter(
-            vec![
-                EventMask::CREATE,
-                EventMask::DELETE,
-                EventMask::MODIFY,
-                EventMask::MOVED_FROM,
-                EventMask::MOVED_TO,
-            ]
-            .into_iter(),
-        );
+        let now = Instant::now();
         if let Ok(events) = self.inotify.read_events(&mut self.buffer) {
             for event in events {
                 if event.mask.contains(EventMask::IGNORED) {
                     // watch was removed so remove it from our map
                     self.map.remove(&event.wd);
                 }
-                if !event.mask.intersects(mm) {
+                let kind = if event.mask.contains(EventMask::CREATE) {
+                    EventKind::Created
+                } else if event.mask.contains(EventMask::DELETE) {
+                    EventKind::Deleted
+                } else if event.mask.contains(EventMask::MODIFY) {
+                    EventKind::Modified
+                } else if event.mask.contains(EventMask::MOVED_FROM) {
+                    EventKind::MovedFrom(event.cookie)
+                } else if event.mask.contains(EventMask::MOVED_TO) {
+                    EventKind::MovedTo(event.cookie)
+                } else {
                     continue;
+                };
+                let updated_path = match self.map.get(&event.wd) {
+                    Some(path) => path.clone(),
+                    None => continue,
+                };
+                let name = event
+                    .name
+                    .and_then(|name| name.to_str())
+                    .map(|name| name.to_string());
+
+                if let (true, Some(name)) = (event.mask.contains(EventMask::ISDIR), &name) {
+                    let path = Path::new(&updated_path).join(name);
+                    match kind {
+                        EventKind::MovedFrom(cookie) => {
+                            self.moved_dirs.insert(cookie, path, now);
+                        }
+                        EventKind::MovedTo(cookie) => {
+                            if let Some(from) = self.moved_dirs.take(cookie) {
+                                InotifyWatcher::move_watches(&mut self.map, &from, &path);
+                            }
+                        }
+                        _ => {}
+                    }
                 }
+
                 result.push(FileEvent {
-                    updated_path: self.map[&event.wd].clone(),
+                    updated_path,
+                    name,
+                    kind,
                 })
             }
         }
+        // directories moved outside of watched tree will be scanned again
+        // if they appear somewhere inside it
+        for path in self.moved_dirs.pop_expired(now) {
+            self.remove_watches(&path);
+        }
         result
     }
 }
+
+impl InotifyWatcher {
+    /// Updates paths of all watches inside moved directory (including itself)
+    fn move_watches(map: &mut HashMap<WatchDescriptor, String>, from: &Path, to: &Path) {
+        for path in map.values_mut() {
+            if let Ok(suffix) = Path::new(path).strip_prefix(from) {
+                let new_path = if suffix.as_os_str().is_empty() {
+                    to.to_path_buf()
+                } else {
+                    to.join(suffix)
+                };
+                if let Some(new_path) = new_path.to_str() {
+                    *path = new_path.to_string();
+                }
+            }
+        }
+    }
+
+    /// Removes all watches inside directory (including itself)
+    fn remove_watches(&mut self, dir: &Path) {
+        let removed: Vec<_> = self
+            .map
+            .iter()
+            .filter(|(_, path)| Path::new(path).starts_with(dir))
+            .map(|(wd, _)| wd.clone())
+            .collect();
+        for wd in removed {
+            self.map.remove(&wd);
+            let _ = self.inotify.rm_watch(wd);
+        }
+    }
+}
@@ -3,7 +3,7 @@ use std::sync::mpsc::Receiver;
 
 use fsevent::{Event, FsEvent, StreamFlags};
 
-use crate::watcher::{FileEvent, Watcher, WatcherError};
+use crate::watcher::{EventKind, FileEvent, Watcher, WatcherError};
 
 struct FsEventWatcher {
     fsevent: FsEvent,
@@ -42,9 +42,34 @@ impl Watcher for FsEventWatcher {
             if !event.flag.intersects(flags) {
                 continue;
             }
-            if let Some(parent) = PathBuf::from(event.path).parent() {
+            let path = PathBuf::from(event.path);
+            // events are coalesced so check whether entry still exists
+            // renames don't provide any info to match old and new names
+            let kind = if event
+                .flag
+                .intersects(StreamFlags::ITEM_REMOVED | StreamFlags::ITEM_RENAMED)
+            {
+                if path.exists() {
+                    EventKind::Created
+                } else {
+                    EventKind::Deleted
+                }
+            } else if event
+                .flag
+                .intersects(StreamFlags::ITEM_CREATED | StreamFlags::ITEM_CLONED)
+            {
+                EventKind::Created
+            } else {
+                EventKind::Modified
+            };
+            if let Some(parent) = path.parent() {
                 result.push(FileEvent {
                     updated_path: parent.to_str().unwrap().to_string(),
+                    name: path
+                        .file_name()
+                        .and_then(|name| name.to_str())
+                        .map(|name| name.to_string()),
+                    kind,
                 })
             }
         }
@@ -1,3 +1,5 @@
+use std::path::{Path, PathBuf};
+
 #[cfg(target_os = "linux")]
 pub use linux::new_watcher;
 #[cfg(target_os = "macos")]
@@ -5,10 +7,15 @@ pub use macos::new_watcher;
 #[cfg(target_os = "windows")]
 pub use windows::new_watcher;
 
+pub use pending_moves::PendingMoves;
+pub use poll::{PollWatcher, MIN_POLL_INTERVAL};
+
 #[cfg(target_os = "linux")]
 mod linux;
 #[cfg(target_os = "macos")]
 mod macos;
+mod pending_moves;
+mod poll;
 #[cfg(windows)]
 mod windows;
 
@@ -20,9 +27,39 @@ pub enum WatcherError {
     Unknown,
 }
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EventKind {
+    Created,
+    Deleted,
+    Modified,
+    /// Entry was moved out of directory
+    ///
+    /// Cookie is the same as in matching `MovedTo` event
+    MovedFrom(u32),
+    /// Entry was moved into directory
+    ///
+    /// Cookie is the same as in matching `MovedFrom` event
+    MovedTo(u32),
+}
+
 #[derive(Debug)]
 pub struct FileEvent {
+    /// Path of directory where change happened
     pub updated_path: String,
+
+    /// Name of changed entry inside directory or `None` if it is unknown
+    pub name: Option<String>,
+
+    pub kind: EventKind,
+}
+
+impl FileEvent {
+    /// Returns full path of changed entry if its name is known
+    pub fn entry_path(&self) -> Option<PathBuf> {
+        self.name
+            .as_ref()
+            .map(|name| Path::new(&self.updated_path).join(name))
+    }
 }
 
 pub trait Watcher {
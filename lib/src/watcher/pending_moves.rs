@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Time to wait for `MovedTo` event after matching `MovedFrom` one was read
+///
+/// Events of a single move can be split between two reads if buffer is full,
+/// so unmatched moves are kept until the next read instead of being dropped at once.
+pub const MOVE_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Sources of moves that don't have matching `MovedTo` event yet, key is cookie
+#[derive(Debug)]
+pub struct PendingMoves<T> {
+    moves: HashMap<u32, (T, Instant)>,
+}
+
+impl<T> Default for PendingMoves<T> {
+    fn default() -> Self {
+        PendingMoves {
+            moves: HashMap::new(),
+        }
+    }
+}
+
+impl<T> PendingMoves<T> {
+    pub fn insert(&mut self, cookie: u32, from: T, now: Instant) {
+        self.moves.insert(cookie, (from, now));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// Removes moves that waited for `MOVE_TIMEOUT` and returns their sources
+    pub fn pop_expired(&mut self, now: Instant) -> Vec<T> {
+        let expired: Vec<_> = self
+            .moves
+            .iter()
+            .filter(|(_, (_, time))| now.duration_since(*time) >= MOVE_TIMEOUT)
+            .map(|(cookie, _)| *cookie)
+            .collect();
+        expired
+            .into_iter()
+            .filter_map(|cookie| self.moves.remove(&cookie))
+            .map(|(from, _)| from)
+            .collect()
+    }
+
+    /// Removes move with given cookie and returns its source
+    pub fn take(&mut self, cookie: u32) -> Option<T> {
+        self.moves.remove(&cookie).map(|(from, _)| from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use crate::watcher::pending_moves::{PendingMoves, MOVE_TIMEOUT};
+
+    #[test]
+    fn match_in_next_read() {
+        let mut moves = PendingMoves::default();
+        let start = Instant::now();
+
+        moves.insert(1, "dir1", start);
+        moves.insert(2, "dir2", start);
+        assert!(moves.pop_expired(start).is_empty());
+
+        let later = start + Duration::from_millis(10);
+        assert_eq!(moves.take(1), Some("dir1"));
+        assert_eq!(moves.take(1), None);
+        assert_eq!(moves.take(3), None);
+
+        assert!(moves.pop_expired(later).is_empty());
+        assert_eq!(moves.pop_expired(start + MOVE_TIMEOUT), vec!["dir2"]);
+        assert!(moves.is_empty());
+    }
+}
@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::watcher::{EventKind, FileEvent, Watcher, WatcherError};
+
+/// Max time to wait before checking whether watcher should stop
+const STOP_CHECK_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Shortest interval between checks, shorter ones would keep a core busy
+pub const MIN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Watcher that periodically checks modification time of all added directories
+///
+/// Works on any filesystem, including network and FUSE mounts that don't deliver
+/// change notifications. Only changes of directory contents are detected
+/// (created, deleted or renamed entries) since writing to a file doesn't
+/// update modification time of its parent directory.
+pub struct PollWatcher {
+    dirs_tx: Sender<(String, SystemTime)>,
+    rx: Receiver<FileEvent>,
+    join_handle: Option<JoinHandle<()>>,
+    should_stop: Arc<AtomicBool>,
+}
+
+impl PollWatcher {
+    /// Creates new watcher that checks all directories once per `interval`
+    ///
+    /// Interval is raised to [`MIN_POLL_INTERVAL`] if it is shorter
+    pub fn new(interval: Duration) -> Self {
+        let interval = interval.max(MIN_POLL_INTERVAL);
+        let (dirs_tx, dirs_rx) = std::sync::mpsc::channel();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let should_stop = Arc::new(AtomicBool::new(false));
+
+        let join_handle = {
+            let should_stop = Arc::clone(&should_stop);
+            std::thread::spawn(move || poll_changes(interval, dirs_rx, tx, should_stop))
+        };
+
+        PollWatcher {
+            dirs_tx,
+            rx,
+            join_handle: Some(join_handle),
+            should_stop,
+        }
+    }
+}
+
+impl Watcher for PollWatcher {
+    fn add_dir(&mut self, path: String) -> Result<(), WatcherError> {
+        let modified = get_modified(&path).ok_or(WatcherError::Unknown)?;
+        self.dirs_tx
+            .send((path, modified))
+            .map_err(|_| WatcherError::Unknown)
+    }
+
+    fn read_events(&mut self) -> Vec<FileEvent> {
+        self.rx.try_iter().collect()
+    }
+}
+
+impl Drop for PollWatcher {
+    fn drop(&mut self) {
+        self.should_stop.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.join_handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn get_modified(path: &str) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn poll_changes(
+    interval: Duration,
+    dirs_rx: Receiver<(String, SystemTime)>,
+    tx: Sender<FileEvent>,
+    should_stop: Arc<AtomicBool>,
+) {
+    // all directories are owned by this thread so adding new ones is never
+    // blocked by slow polling (e.g. on network mounts)
+    let mut dirs: HashMap<String, SystemTime> = HashMap::new();
+    let mut next_poll = Instant::now() + interval;
+
+    while !should_stop.load(Ordering::SeqCst) {
+        dirs.extend(dirs_rx.try_iter());
+
+        let now = Instant::now();
+        if now < next_poll {
+            std::thread::sleep(STOP_CHECK_INTERVAL.min(next_poll - now));
+            continue;
+        }
+
+        dirs.retain(|path, modified| {
+            // pass over slow mounts can take long, so stop without checking the rest
+            if should_stop.load(Ordering::SeqCst) {
+                return true;
+            }
+            match get_modified(path) {
+                Some(time) => {
+                    if time != *modified {
+                        *modified = time;
+                        let _ = tx.send(FileEvent {
+                            updated_path: path.clone(),
+                            name: None,
+                            kind: EventKind::Modified,
+                        });
+                    }
+                    true
+                }
+                // directory was removed, this change is detected by its parent
+                None => false,
+            }
+        });
+        next_poll = Instant::now() + interval;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use crate::watcher::poll::{PollWatcher, MIN_POLL_INTERVAL};
+    use crate::watcher::{EventKind, Watcher};
+
+    #[test]
+    fn detects_created_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().to_str().unwrap().to_string();
+
+        let mut watcher = PollWatcher::new(Duration::ZERO);
+        watcher.add_dir(path.clone()).unwrap();
+        std::fs::write(dir.path().join("file"), "data").unwrap();
+
+        let deadline = Instant::now() + 20 * MIN_POLL_INTERVAL;
+        let mut events = vec![];
+        while events.is_empty() && Instant::now() < deadline {
+            std::thread::sleep(MIN_POLL_INTERVAL / 2);
+            events = watcher.read_events();
+        }
+        drop(watcher);
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].updated_path, path);
+        assert_eq!(events[0].kind, EventKind::Modified);
+    }
+}
@@ -8,12 +8,14 @@ use widestring::{U16CString, U16String};
 use windows_sys::Win32::Foundation::{CloseHandle, HANDLE, INVALID_HANDLE_VALUE};
 use windows_sys::Win32::Storage::FileSystem;
 use windows_sys::Win32::Storage::FileSystem::{
-    ReadDirectoryChangesW, FILE_FLAG_BACKUP_SEMANTICS, FILE_FLAG_OVERLAPPED, FILE_LIST_DIRECTORY,
-    FILE_NOTIFY_CHANGE_DIR_NAME, FILE_NOTIFY_CHANGE_FILE_NAME, FILE_NOTIFY_CHANGE_SIZE,
-    FILE_NOTIFY_INFORMATION, FILE_SHARE_DELETE, FILE_SHARE_READ, FILE_SHARE_WRITE, OPEN_EXISTING,
+    ReadDirectoryChangesW, FILE_ACTION_ADDED, FILE_ACTION_MODIFIED, FILE_ACTION_REMOVED,
+    FILE_ACTION_RENAMED_NEW_NAME, FILE_ACTION_RENAMED_OLD_NAME, FILE_FLAG_BACKUP_SEMANTICS,
+    FILE_FLAG_OVERLAPPED, FILE_LIST_DIRECTORY, FILE_NOTIFY_CHANGE_DIR_NAME,
+    FILE_NOTIFY_CHANGE_FILE_NAME, FILE_NOTIFY_CHANGE_SIZE, FILE_NOTIFY_INFORMATION,
+    FILE_SHARE_DELETE, FILE_SHARE_READ, FILE_SHARE_WRITE, OPEN_EXISTING,
 };
 
-use crate::watcher::{FileEvent, Watcher, WatcherError};
+use crate::watcher::{EventKind, FileEvent, Watcher, WatcherError};
 
 const BUFFER_LEN: usize = 48 * 1024;
 
@@ -92,6 +94,9 @@ fn watch_changes(
     let (tx, rx) = std::sync::mpsc::channel();
     let mut buffer = vec![0u32; BUFFER_LEN];
     let join_handle = std::thread::spawn(move || {
+        // windows doesn't provide cookies, but old and new names of renamed entry
+        // are always reported one after another, so cookies are generated here
+        let mut cookie = 0u32;
         while !should_stop.load(Ordering::SeqCst) {
             let mut bytes_returned = 0u32;
 
@@ -130,15 +135,35 @@ fn watch_changes(
                     U16String::from_ptr(info.FileName.as_ptr(), (info.FileNameLength / 2) as usize)
                 };
 
+                let kind = match info.Action {
+                    FILE_ACTION_ADDED => Some(EventKind::Created),
+                    FILE_ACTION_REMOVED => Some(EventKind::Deleted),
+                    FILE_ACTION_MODIFIED => Some(EventKind::Modified),
+                    FILE_ACTION_RENAMED_OLD_NAME => Some(EventKind::MovedFrom(cookie)),
+                    FILE_ACTION_RENAMED_NEW_NAME => {
+                        let kind = EventKind::MovedTo(cookie);
+                        cookie = cookie.wrapping_add(1);
+                        Some(kind)
+                    }
+                    _ => None,
+                };
+
                 let full_path = root.join(filename.to_os_string());
-                if let Some(parent) = full_path
-                    .parent()
-                    .and_then(|p| U16CString::from_os_str(p).ok())
-                    .and_then(|p| crate::platform::get_long_path(&p))
-                    .and_then(|p| p.to_string().ok())
-                {
+                if let (Some(kind), Some(parent)) = (
+                    kind,
+                    full_path
+                        .parent()
+                        .and_then(|p| U16CString::from_os_str(p).ok())
+                        .and_then(|p| crate::platform::get_long_path(&p))
+                        .and_then(|p| p.to_string().ok()),
+                ) {
                     tx.send(FileEvent {
                         updated_path: parent,
+                        name: full_path
+                            .file_name()
+                            .and_then(|name| name.to_str())
+                            .map(|name| name.to_string()),
+                        kind,
                     })
                     .unwrap();
                 }